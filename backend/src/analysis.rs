@@ -0,0 +1,156 @@
+use num::{traits::float::TotalOrder, Float, NumCast};
+
+#[derive(Debug, Clone, Default)]
+pub struct FringeAnalysis<T: Float>
+{
+    pub maxima: Vec<usize>,
+    /// the lowest sample between each pair of neighbouring maxima
+    pub minima: Vec<usize>,
+    /// mean distance between neighbouring maxima
+    pub spacing: T,
+    /// (Imax - Imin) / (Imax + Imin) about the brightest maximum
+    pub visibility: T
+}
+
+/// local maxima with an intensity above `threshold` times the largest sample
+pub fn find_maxima<T: Float>(data: &[T], threshold: T) -> Vec<usize>
+{
+    let max = data.iter().fold(T::zero(), |a, b| a.max(*b));
+    let min_value = max * threshold;
+    let mut result = Vec::new();
+    if data.len() < 3 || max <= T::zero() { return result; }
+    
+    let mut i = 1;
+    while i < data.len() - 1
+    {
+        if data[i] <= data[i - 1] || data[i] < min_value
+        {
+            i += 1;
+            continue;
+        }
+        
+        // walk over flat tops
+        let mut j = i;
+        while j < data.len() - 1 && data[j + 1] == data[i]
+        {
+            j += 1;
+        }
+        
+        if j < data.len() - 1 && data[j + 1] < data[i]
+        {
+            result.push((i + j) / 2);
+        }
+        i = j + 1;
+    }
+    
+    return result;
+}
+
+pub fn find_minima<T: Float>(data: &[T], maxima: &[usize]) -> Vec<usize>
+{
+    return maxima.windows(2).map(|w|
+    {
+        let mut index = w[0];
+        for i in w[0]..w[1]
+        {
+            if data[i] < data[index]
+            {
+                index = i;
+            }
+        }
+        return index;
+    }).collect();
+}
+
+/// `positions` are the distances of each sample along the screen
+pub fn analyse_fringes<T: Float + TotalOrder>(positions: &[T], intensity: &[T], threshold: T) -> FringeAnalysis<T>
+{
+    let maxima = find_maxima(intensity, threshold);
+    let minima = find_minima(intensity, &maxima);
+    
+    let spacing = match maxima.len()
+    {
+        0 | 1 => T::zero(),
+        n =>
+        {
+            let span = positions[maxima[n - 1]] - positions[maxima[0]];
+            span / <T as NumCast>::from(n - 1).unwrap()
+        }
+    };
+    
+    let visibility = match maxima.iter().enumerate().max_by(|a, b| intensity[*a.1].total_cmp(&intensity[*b.1]))
+    {
+        None => T::zero(),
+        Some((i, m)) =>
+        {
+            // minima i - 1 and i are either side of maxima i
+            let adjacent: Vec<T> = [i.wrapping_sub(1), i].iter()
+                .filter_map(|j| minima.get(*j)).map(|j| intensity[*j]).collect();
+            
+            let i_max = intensity[*m];
+            let i_min = match adjacent.len()
+            {
+                0 => T::zero(),
+                n => adjacent.iter().fold(T::zero(), |a, b| a + *b) / <T as NumCast>::from(n).unwrap()
+            };
+            
+            if i_max + i_min <= T::zero() { T::zero() }
+            else { (i_max - i_min) / (i_max + i_min) }
+        }
+    };
+    
+    return FringeAnalysis { maxima, minima, spacing, visibility };
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SlitTheory<T: Float>
+{
+    pub separation: T,
+    pub width: T,
+    /// distance from the slits to the screen
+    pub distance: T,
+    pub wavelength: T
+}
+
+impl<T: Float> SlitTheory<T>
+{
+    /// angles of the interference maxima, d sin(theta) = m lambda
+    pub fn interference_maxima(&self) -> Vec<(i32, T)>
+    {
+        return orders(self.separation, self.wavelength, false);
+    }
+    /// angles of the single slit minima, a sin(theta) = m lambda
+    pub fn single_slit_minima(&self) -> Vec<(i32, T)>
+    {
+        return orders(self.width, self.wavelength, true);
+    }
+    /// small angle fringe spacing on the screen
+    pub fn fringe_spacing(&self) -> T
+    {
+        if self.separation <= T::zero() { return T::zero(); }
+        return self.wavelength * self.distance / self.separation;
+    }
+    /// offset from the central axis on a parallel screen
+    pub fn screen_offset(&self, angle: T) -> T
+    {
+        return self.distance * angle.tan();
+    }
+}
+
+fn orders<T: Float>(d: T, lambda: T, skip_zero: bool) -> Vec<(i32, T)>
+{
+    let mut result = Vec::new();
+    if d <= T::zero() || lambda <= T::zero() { return result; }
+    
+    let max = (d / lambda).floor().to_i32().unwrap_or(0);
+    for m in -max..=max
+    {
+        if skip_zero && m == 0 { continue; }
+        
+        let sin = <T as NumCast>::from(m).unwrap() * lambda / d;
+        if sin.abs() >= T::one() { continue; }
+        result.push((m, sin.asin()));
+    }
+    
+    return result;
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
+
+use crate::RealFftPlan;
+
+/// magnitudes below this fraction of the peak are raised to it before the log
+pub const LOG_FLOOR: f64 = 1e-6;
+
+/// inverse transform of the log of `magnitudes`, the `plan.spectrum_len()` bins of a real signal
+pub fn real_cepstrum<T>(plan: &RealFftPlan<T>, magnitudes: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let peak = magnitudes.iter().fold(T::ZERO, |m, v| m.max(v.abs()));
+    let floor = (peak * T::from(LOG_FLOOR).unwrap()).max(T::min_positive_value());
+    let log: Vec<Complex<T>> = magnitudes.iter()
+        .map(|m| Complex::new(m.abs().max(floor).ln(), T::ZERO))
+        .collect();
+    return plan.inverse_normalised(&log);
+}
+
+/// spectrum with the given magnitudes whose phase is minimum, from the folded cepstrum
+pub fn minimum_phase<T>(plan: &RealFftPlan<T>, magnitudes: &[T]) -> Vec<Complex<T>>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let n = plan.len();
+    if n == 0 { return Vec::new(); }
+    
+    let mut c = real_cepstrum(plan, magnitudes);
+    // anticausal half folded onto the causal half
+    let two = T::ONE + T::ONE;
+    for (i, v) in c.iter_mut().enumerate().skip(1)
+    {
+        if 2 * i < n
+        {
+            *v = *v * two;
+        }
+        else if 2 * i > n
+        {
+            *v = T::ZERO;
+        }
+    }
+    
+    return plan.forward(&c).iter().map(|l| l.exp()).collect();
+}
\ No newline at end of file
@@ -3,6 +3,8 @@ use std::fmt::Display;
 use bytemuck::{Pod, Zeroable};
 use zene_structs::{Vector3, Vector4};
 
+use crate::{wave_length_colour, wave_length_false_colour};
+
 #[derive(Debug, Copy, Clone, Default)]
 pub struct Colour
 {
@@ -73,4 +75,30 @@ impl From<[f32; 4]> for Colour
             value[2],
             value[3]);
     }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColourMap
+{
+    /// true colour, with false colour outside the visible range
+    #[default]
+    Visible,
+    /// the band is stretched over the visible range
+    FalseColour
+}
+
+impl ColourMap
+{
+    pub fn colour(&self, wl: f32, band: (f32, f32), gamma: f32) -> Vector3<f32>
+    {
+        return match self
+        {
+            ColourMap::Visible => wave_length_false_colour(wl, gamma),
+            ColourMap::FalseColour =>
+            {
+                let t = ((wl - band.0) / (band.1 - band.0)).clamp(0.0, 1.0);
+                wave_length_colour(400.0 + (t * 300.0), gamma)
+            }
+        };
+    }
 }
\ No newline at end of file
@@ -0,0 +1,200 @@
+use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
+
+use crate::RealFftPlan;
+
+/// kernels this short are summed directly, the transforms cost more
+pub const DIRECT_MAX: usize = 32;
+
+/// `x` summed into `n` values, so longer sequences wrap and shorter ones are zero padded
+fn wrap<T: Float>(x: &[T], n: usize) -> Vec<T>
+{
+    let mut result = vec![T::zero(); n];
+    for (i, v) in x.iter().enumerate()
+    {
+        result[i % n] = result[i % n] + *v;
+    }
+    return result;
+}
+
+/// inverse of the product of the spectra of `a` and `b` over `plan.len()`, conjugating `b`'s when `conj`
+fn spectrum_product<T>(plan: &RealFftPlan<T>, a: &[T], b: &[T], conj: bool) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let n = plan.len();
+    let fa = plan.forward(&wrap(a, n));
+    let fb = plan.forward(&wrap(b, n));
+    
+    let product: Vec<Complex<T>> = fa.iter().zip(&fb).map(|(x, y)| match conj
+    {
+        true => x * y.conj(),
+        false => x * y
+    }).collect();
+    return plan.inverse_normalised(&product);
+}
+
+/// plan length for `convolve_with_plan` and `correlate_with_plan` of these lengths,
+/// a power of 2 so the transforms are fast
+pub fn convolution_len(a: usize, b: usize) -> usize
+{
+    if a == 0 || b == 0 { return 0; }
+    
+    return (a + b - 1).next_power_of_two();
+}
+
+/// linear convolution by summing, `a.len() + b.len() - 1` long
+pub fn convolve_direct<T: Float>(a: &[T], b: &[T]) -> Vec<T>
+{
+    if a.is_empty() || b.is_empty() { return Vec::new(); }
+    
+    let mut result = vec![T::zero(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate()
+    {
+        for (j, y) in b.iter().enumerate()
+        {
+            result[i + j] = result[i + j] + (*x * *y);
+        }
+    }
+    return result;
+}
+
+/// linear convolution, `a.len() + b.len() - 1` long.
+/// padded to a power of 2 for the transforms so nothing wraps around
+pub fn convolve<T>(a: &[T], b: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    if a.len().min(b.len()) <= DIRECT_MAX
+    {
+        return convolve_direct(a, b);
+    }
+    
+    let plan = RealFftPlan::new(convolution_len(a.len(), b.len()));
+    return convolve_with_plan(&plan, a, b);
+}
+/// `convolve` reusing `plan`, which must be at least `a.len() + b.len() - 1` long
+pub fn convolve_with_plan<T>(plan: &RealFftPlan<T>, a: &[T], b: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    if a.len().min(b.len()) <= DIRECT_MAX
+    {
+        return convolve_direct(a, b);
+    }
+    
+    let len = a.len() + b.len() - 1;
+    if plan.len() < len
+    {
+        panic!("plan is for a length of {}, the convolution needs {}", plan.len(), len);
+    }
+    let mut result = spectrum_product(plan, a, b, false);
+    result.truncate(len);
+    return result;
+}
+
+/// the centre `a.len()` values of `convolve(a, kernel)`, for smoothing with a centred kernel
+pub fn convolve_same<T>(a: &[T], kernel: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    return same(a, kernel, convolve(a, kernel));
+}
+/// `convolve_same` reusing `plan`, as for `convolve_with_plan`
+pub fn convolve_same_with_plan<T>(plan: &RealFftPlan<T>, a: &[T], kernel: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    return same(a, kernel, convolve_with_plan(plan, a, kernel));
+}
+fn same<T: Float>(a: &[T], kernel: &[T], full: Vec<T>) -> Vec<T>
+{
+    if kernel.is_empty() { return vec![T::zero(); a.len()]; }
+    
+    let start = (kernel.len() - 1) / 2;
+    return full.into_iter().skip(start).take(a.len()).collect();
+}
+
+/// cross correlation, sum of `a[n + lag] b[n]`.
+/// index `k` is the lag `k - (b.len() - 1)`, so `a.len() + b.len() - 1` long
+pub fn correlate<T>(a: &[T], b: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let reversed: Vec<T> = b.iter().rev().copied().collect();
+    return convolve(a, &reversed);
+}
+/// `correlate` reusing `plan`, as for `convolve_with_plan`
+pub fn correlate_with_plan<T>(plan: &RealFftPlan<T>, a: &[T], b: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let reversed: Vec<T> = b.iter().rev().copied().collect();
+    return convolve_with_plan(plan, a, &reversed);
+}
+
+/// circular convolution of period `a.len()`, `b` wraps if it is longer
+pub fn convolve_circular<T>(a: &[T], b: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    if a.is_empty() || b.len() <= DIRECT_MAX
+    {
+        return circular_direct(a, b, false);
+    }
+    return spectrum_product(&RealFftPlan::new(a.len()), a, b, false);
+}
+/// `convolve_circular` reusing `plan`, which must be `a.len()` long
+pub fn convolve_circular_with_plan<T>(plan: &RealFftPlan<T>, a: &[T], b: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    if a.is_empty() || b.len() <= DIRECT_MAX
+    {
+        return circular_direct(a, b, false);
+    }
+    check_period(plan.len(), a.len());
+    return spectrum_product(plan, a, b, false);
+}
+
+/// circular cross correlation of period `a.len()`, index `k` is the lag `k`
+pub fn correlate_circular<T>(a: &[T], b: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    if a.is_empty() || b.len() <= DIRECT_MAX
+    {
+        return circular_direct(a, b, true);
+    }
+    return spectrum_product(&RealFftPlan::new(a.len()), a, b, true);
+}
+/// `correlate_circular` reusing `plan`, which must be `a.len()` long
+pub fn correlate_circular_with_plan<T>(plan: &RealFftPlan<T>, a: &[T], b: &[T]) -> Vec<T>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    if a.is_empty() || b.len() <= DIRECT_MAX
+    {
+        return circular_direct(a, b, true);
+    }
+    check_period(plan.len(), a.len());
+    return spectrum_product(plan, a, b, true);
+}
+
+fn check_period(plan: usize, n: usize)
+{
+    if plan != n
+    {
+        panic!("plan is for a length of {}, the period is {}", plan, n);
+    }
+}
+
+/// circular convolution or correlation of period `a.len()` by summing
+fn circular_direct<T: Float>(a: &[T], b: &[T], correlate: bool) -> Vec<T>
+{
+    let n = a.len();
+    let mut result = vec![T::zero(); n];
+    if n == 0 { return result; }
+    
+    for (j, y) in b.iter().enumerate()
+    {
+        for (i, r) in result.iter_mut().enumerate()
+        {
+            let k = match correlate
+            {
+                true => (j + i) % n,
+                false => (i + n - (j % n)) % n
+            };
+            *r = *r + (a[k] * *y);
+        }
+    }
+    return result;
+}
\ No newline at end of file
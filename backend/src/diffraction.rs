@@ -40,6 +40,8 @@ pub struct Slit<'a, T: Float>
     pub width: T,
     pub position: Vector2<T>,
     direction: Vector2<T>,
+    /// phase offset of the light leaving the slit
+    pub phase: T,
     pub waves: &'a [Wave<T>]
 }
 
@@ -51,9 +53,15 @@ impl<'a, T: Float> Slit<'a, T>
             width,
             position,
             direction: direction.normalised(),
+            phase: T::zero(),
             waves
         };
     }
+    pub fn with_phase(mut self, phase: T) -> Self
+    {
+        self.phase = phase;
+        return self;
+    }
     
     pub fn get_direction(&self) -> Vector2<T>
     {
@@ -89,13 +97,21 @@ impl<'a, T: Float> Slit<'a, T>
         let args_o = self.diff_args(x);
         match args_o
         {
-            Some(args) =>
+            Some(args) if self.phase.is_zero() =>
             {
                 for (res, wave) in LambdaZip::new(result.iter_mut(), self.waves.iter())
                 {
                     *res = *res + wave.diffract(args);
                 }
             },
+            Some(args) =>
+            {
+                let shift = Complex::from_polar(T::ONE, self.phase);
+                for (res, wave) in LambdaZip::new(result.iter_mut(), self.waves.iter())
+                {
+                    *res = *res + (wave.diffract(args) * shift);
+                }
+            },
             None => {}
         };
     }
@@ -1,9 +1,7 @@
-use std::mem::replace;
-
 use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float, NumCast, Zero};
 use zene_structs::{Vector2, Vector3};
 
-use crate::{IntoF32, Slit};
+use crate::{IntoF32, ScreenProfile, Slit};
 
 #[derive(Debug, Clone, Default)]
 pub struct EMEnv<T: Float>
@@ -21,42 +19,92 @@ impl<T> EMEnv<T>
         };
     }
     
-    fn lerp(&self, x: T) -> Vector2<T>
+    pub fn lerp(&self, x: T) -> Vector2<T>
     {
         let a = self.screen.0;
         let b = self.screen.1;
         return a + ((b - a) * x);
     }
     
-    pub fn generate_pattern<S>(&self, slits: &[Slit<'_, T>], wave_map: &[(T, Vector3)], samples: &mut [S])
-        where S: From<Vector3>
+    /// fraction along the screen of sample `i` of `count`, a single sample is in the middle
+    fn sample_x(i: usize, count: usize) -> T
     {
-        let mut buffer: Vec<(T, Complex<T>)> = wave_map.iter().map(|w| (w.0, Complex::<T>::ZERO)).collect();
+        if count < 2 { return T::from(0.5).unwrap(); }
         
-        let step = T::one() / <T as NumCast>::from(samples.len() - 1).unwrap();
-        let mut x = T::zero();
+        return <T as NumCast>::from(i).unwrap() / <T as NumCast>::from(count - 1).unwrap();
+    }
+    /// calls `f` with the index, fraction along the screen, point and the field of each wavelength
+    /// for `count` samples across the screen
+    fn sample_fields<W, F>(&self, slits: &[Slit<'_, T>], wavelengths: W, count: usize, mut f: F)
+        where W: Iterator<Item = T>,
+            F: FnMut(usize, T, Vector2<T>, &[(T, Complex<T>)])
+    {
+        let mut buffer: Vec<(T, Complex<T>)> = wavelengths.map(|w| (w, Complex::<T>::ZERO)).collect();
         
-        for p in samples
+        for i in 0..count
         {
+            let x = Self::sample_x(i, count);
             let s_p = self.lerp(x);
-            x = x + step;
             
             for s in slits
             {
                 s.calculate_intensity(s_p, &mut buffer);
             }
             
+            f(i, x, s_p, &buffer);
+            for (_, c) in &mut buffer
+            {
+                *c = Complex::<T>::ZERO;
+            }
+        }
+    }
+    
+    pub fn generate_pattern<S>(&self, slits: &[Slit<'_, T>], wave_map: &[(T, Vector3)], samples: &mut [S])
+        where S: From<Vector3>
+    {
+        self.sample_fields(slits, wave_map.iter().map(|w| w.0), samples.len(), |i, _, _, fields|
+        {
             let mut sample = Vector3::zero();
+            for ((_, c), (_, colour)) in fields.iter().zip(wave_map.iter())
+            {
+                sample += *colour * c.norm_sqr().into_f32();
+            }
+            
+            samples[i] = sample.into();
+        });
+    }
+    
+    /// total intensity at each sample, `wavelengths` must be ordered as the slits' waves
+    pub fn generate_intensity(&self, slits: &[Slit<'_, T>], wavelengths: &[T], samples: &mut [T])
+    {
+        self.sample_fields(slits, wavelengths.iter().copied(), samples.len(), |i, _, _, fields|
+        {
+            samples[i] = fields.iter().fold(T::ZERO, |t, (_, c)| t + c.norm_sqr());
+        });
+    }
+    
+    pub fn generate_profile(&self, slits: &[Slit<'_, T>], wavelengths: &[T], count: usize, per_wave: bool) -> ScreenProfile<T>
+    {
+        let mut profile = ScreenProfile::<T>::new(wavelengths.to_vec(), count);
+        let length = self.screen.0.distance(self.screen.1);
+        
+        self.sample_fields(slits, wavelengths.iter().copied(), count, |_, x, s_p, fields|
+        {
+            profile.position.push(x * length);
+            profile.points.push(s_p);
             
-            // sum total and clear buffer
-            for ((_, c), (_, colour)) in buffer.iter_mut().zip(wave_map.iter())
+            let intensities = fields.iter().map(|(_, c)| c.norm_sqr());
+            if per_wave
             {
-                let c = replace(c, Complex::<T>::ZERO);
-                let i = *colour * c.norm_sqr().into_f32();
-                sample += i;
+                let waves: Vec<T> = intensities.collect();
+                profile.total.push(waves.iter().fold(T::ZERO, |t, i| t + *i));
+                profile.per_wave.push(waves);
+                return;
             }
             
-            *p = sample.into();
-        }
+            profile.total.push(intensities.fold(T::ZERO, |t, i| t + i));
+        });
+        
+        return profile;
     }
 }
\ No newline at end of file
@@ -0,0 +1,408 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp
+{
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function
+{
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Log,
+    Sqrt,
+    Abs,
+    Floor,
+    Ceil,
+    Sign,
+    Min,
+    Max,
+    Atan2
+}
+
+impl Function
+{
+    fn from_name(name: &str) -> Option<Function>
+    {
+        return Some(match name
+        {
+            "sin" => Function::Sin,
+            "cos" => Function::Cos,
+            "tan" => Function::Tan,
+            "asin" => Function::Asin,
+            "acos" => Function::Acos,
+            "atan" => Function::Atan,
+            "sinh" => Function::Sinh,
+            "cosh" => Function::Cosh,
+            "tanh" => Function::Tanh,
+            "exp" => Function::Exp,
+            "ln" => Function::Ln,
+            "log" => Function::Log,
+            "sqrt" => Function::Sqrt,
+            "abs" => Function::Abs,
+            "floor" => Function::Floor,
+            "ceil" => Function::Ceil,
+            "sign" => Function::Sign,
+            "min" => Function::Min,
+            "max" => Function::Max,
+            "atan2" => Function::Atan2,
+            _ => return None
+        });
+    }
+    fn arg_count(&self) -> usize
+    {
+        return match self
+        {
+            Function::Min | Function::Max | Function::Atan2 => 2,
+            _ => 1
+        };
+    }
+    fn apply(&self, a: f64, b: f64) -> f64
+    {
+        return match self
+        {
+            Function::Sin => a.sin(),
+            Function::Cos => a.cos(),
+            Function::Tan => a.tan(),
+            Function::Asin => a.asin(),
+            Function::Acos => a.acos(),
+            Function::Atan => a.atan(),
+            Function::Sinh => a.sinh(),
+            Function::Cosh => a.cosh(),
+            Function::Tanh => a.tanh(),
+            Function::Exp => a.exp(),
+            Function::Ln => a.ln(),
+            Function::Log => a.log10(),
+            Function::Sqrt => a.sqrt(),
+            Function::Abs => a.abs(),
+            Function::Floor => a.floor(),
+            Function::Ceil => a.ceil(),
+            Function::Sign => if a == 0.0 { 0.0 } else { a.signum() },
+            Function::Min => a.min(b),
+            Function::Max => a.max(b),
+            Function::Atan2 => a.atan2(b)
+        };
+    }
+}
+
+/// parsed expression, variables are indices into the names given to `parse`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr
+{
+    Const(f64),
+    Var(usize),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>)
+}
+
+impl Expr
+{
+    /// `vars` in the order of the names given to `parse`
+    pub fn eval(&self, vars: &[f64]) -> f64
+    {
+        return match self
+        {
+            Expr::Const(v) => *v,
+            Expr::Var(i) => vars.get(*i).copied().unwrap_or(f64::NAN),
+            Expr::Neg(e) => -e.eval(vars),
+            Expr::Binary(op, a, b) =>
+            {
+                let (a, b) = (a.eval(vars), b.eval(vars));
+                match op
+                {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    BinaryOp::Div => a / b,
+                    BinaryOp::Pow => a.powf(b)
+                }
+            },
+            Expr::Call(f, args) =>
+            {
+                let a = args.first().map(|e| e.eval(vars)).unwrap_or(f64::NAN);
+                let b = args.get(1).map(|e| e.eval(vars)).unwrap_or(f64::NAN);
+                f.apply(a, b)
+            }
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError
+{
+    /// character offset into the source
+    pub position: usize,
+    pub message: String
+}
+
+impl Display for ParseError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return write!(f, "{} at {}", self.message, self.position);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token
+{
+    Number(f64),
+    Name(String),
+    Op(char),
+    Open,
+    Close,
+    Comma,
+    End
+}
+
+fn tokenise(src: &str) -> Result<Vec<(Token, usize)>, ParseError>
+{
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len()
+    {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace()
+        {
+            i += 1;
+            continue;
+        }
+        
+        let token = match c
+        {
+            '0'..='9' | '.' =>
+            {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') { i += 1; }
+                // exponent
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') &&
+                    chars.get(i + 1).is_some_and(|n| n.is_ascii_digit() || *n == '-' || *n == '+')
+                {
+                    i += 2;
+                    while i < chars.len() && chars[i].is_ascii_digit() { i += 1; }
+                }
+                let text: String = chars[start..i].iter().collect();
+                let v = text.parse::<f64>().map_err(|_| ParseError {
+                    position: start,
+                    message: format!("invalid number '{text}'")
+                })?;
+                tokens.push((Token::Number(v), start));
+                continue;
+            },
+            c if c.is_alphabetic() || c == '_' =>
+            {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { i += 1; }
+                tokens.push((Token::Name(chars[start..i].iter().collect()), start));
+                continue;
+            },
+            '+' | '-' | '*' | '/' | '^' => Token::Op(c),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            _ => return Err(ParseError { position: i, message: format!("unexpected '{c}'") })
+        };
+        tokens.push((token, start));
+        i += 1;
+    }
+    
+    tokens.push((Token::End, chars.len()));
+    return Ok(tokens);
+}
+
+struct Parser<'a>
+{
+    tokens: Vec<(Token, usize)>,
+    at: usize,
+    vars: &'a [&'a str]
+}
+
+impl<'a> Parser<'a>
+{
+    fn peek(&self) -> &Token
+    {
+        return &self.tokens[self.at].0;
+    }
+    fn position(&self) -> usize
+    {
+        return self.tokens[self.at].1;
+    }
+    fn next(&mut self) -> Token
+    {
+        let t = self.tokens[self.at].0.clone();
+        // always ends on `End`
+        if self.at < self.tokens.len() - 1 { self.at += 1; }
+        return t;
+    }
+    fn error<T>(&self, message: String) -> Result<T, ParseError>
+    {
+        return Err(ParseError { position: self.position(), message });
+    }
+    fn expect(&mut self, token: Token, name: &str) -> Result<(), ParseError>
+    {
+        if *self.peek() != token
+        {
+            return self.error(format!("expected {name}"));
+        }
+        self.next();
+        return Ok(());
+    }
+    
+    /// sums
+    fn expression(&mut self) -> Result<Expr, ParseError>
+    {
+        let mut e = self.term()?;
+        loop
+        {
+            let op = match self.peek()
+            {
+                Token::Op('+') => BinaryOp::Add,
+                Token::Op('-') => BinaryOp::Sub,
+                _ => return Ok(e)
+            };
+            self.next();
+            e = Expr::Binary(op, Box::new(e), Box::new(self.term()?));
+        }
+    }
+    /// products
+    fn term(&mut self) -> Result<Expr, ParseError>
+    {
+        let mut e = self.unary()?;
+        loop
+        {
+            let op = match self.peek()
+            {
+                Token::Op('*') => BinaryOp::Mul,
+                Token::Op('/') => BinaryOp::Div,
+                _ => return Ok(e)
+            };
+            self.next();
+            e = Expr::Binary(op, Box::new(e), Box::new(self.unary()?));
+        }
+    }
+    /// binds looser than powers, so -x^2 is -(x^2)
+    fn unary(&mut self) -> Result<Expr, ParseError>
+    {
+        return match self.peek()
+        {
+            Token::Op('-') =>
+            {
+                self.next();
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            },
+            Token::Op('+') =>
+            {
+                self.next();
+                self.unary()
+            },
+            _ => self.power()
+        };
+    }
+    /// right associative
+    fn power(&mut self) -> Result<Expr, ParseError>
+    {
+        let base = self.atom()?;
+        if *self.peek() != Token::Op('^') { return Ok(base); }
+        
+        self.next();
+        return Ok(Expr::Binary(BinaryOp::Pow, Box::new(base), Box::new(self.unary()?)));
+    }
+    fn atom(&mut self) -> Result<Expr, ParseError>
+    {
+        let position = self.position();
+        return match self.next()
+        {
+            Token::Number(v) => Ok(Expr::Const(v)),
+            Token::Open =>
+            {
+                let e = self.expression()?;
+                self.expect(Token::Close, "')'")?;
+                Ok(e)
+            },
+            Token::Name(name) => self.name(name, position),
+            Token::End => Err(ParseError { position, message: String::from("unexpected end") }),
+            t => Err(ParseError { position, message: format!("unexpected {}", describe(&t)) })
+        };
+    }
+    fn name(&mut self, name: String, position: usize) -> Result<Expr, ParseError>
+    {
+        if let Some(i) = self.vars.iter().position(|v| *v == name)
+        {
+            return Ok(Expr::Var(i));
+        }
+        match name.as_str()
+        {
+            "pi" => return Ok(Expr::Const(std::f64::consts::PI)),
+            "tau" => return Ok(Expr::Const(std::f64::consts::TAU)),
+            "e" => return Ok(Expr::Const(std::f64::consts::E)),
+            _ => {}
+        }
+        
+        let f = match Function::from_name(&name)
+        {
+            Some(f) => f,
+            None => return Err(ParseError { position, message: format!("unknown name '{name}'") })
+        };
+        self.expect(Token::Open, "'(' after function")?;
+        let mut args = vec![self.expression()?];
+        while *self.peek() == Token::Comma
+        {
+            self.next();
+            args.push(self.expression()?);
+        }
+        self.expect(Token::Close, "')'")?;
+        
+        if args.len() != f.arg_count()
+        {
+            return Err(ParseError {
+                position,
+                message: format!("'{name}' takes {} argument(s)", f.arg_count())
+            });
+        }
+        return Ok(Expr::Call(f, args));
+    }
+}
+
+fn describe(token: &Token) -> String
+{
+    return match token
+    {
+        Token::Number(v) => format!("number {v}"),
+        Token::Name(n) => format!("'{n}'"),
+        Token::Op(c) => format!("'{c}'"),
+        Token::Open => String::from("'('"),
+        Token::Close => String::from("')'"),
+        Token::Comma => String::from("','"),
+        Token::End => String::from("end")
+    };
+}
+
+/// parses arithmetic with `+ - * / ^`, brackets, functions such as `sin` and `exp`,
+/// the constants `pi`, `tau` and `e`, and the variables named in `vars`
+pub fn parse(src: &str, vars: &[&str]) -> Result<Expr, ParseError>
+{
+    let mut parser = Parser { tokens: tokenise(src)?, at: 0, vars };
+    let e = parser.expression()?;
+    if *parser.peek() != Token::End
+    {
+        let t = parser.peek().clone();
+        return parser.error(format!("unexpected {}", describe(&t)));
+    }
+    return Ok(e);
+}
\ No newline at end of file
@@ -6,6 +6,8 @@ use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
 pub struct WCache<T>
 {
     nth_roots: Vec<Box<[Complex<T>]>>,
+    /// all roots for lengths that are not a power of 2
+    roots: Vec<Box<[Complex<T>]>>,
     invert: bool
 }
 impl<T: Float + ConstOne + ConstZero + FloatConst> WCache<T>
@@ -13,7 +15,7 @@ impl<T: Float + ConstOne + ConstZero + FloatConst> WCache<T>
     pub fn new(invert: bool) -> Self
     {
         let nth_roots = vec![compute_nth_roots(2, invert)];
-        return Self { nth_roots, invert };
+        return Self { nth_roots, roots: Vec::new(), invert };
     }
     pub fn get_nth_roots(&self, power: usize) -> &[Complex<T>]
     {
@@ -33,6 +35,54 @@ impl<T: Float + ConstOne + ConstZero + FloatConst> WCache<T>
             self.nth_roots.push(compute_nth_roots(2 << x, self.invert));
         }
     }
+    pub fn get_roots(&self, n: usize) -> Option<&[Complex<T>]>
+    {
+        return self.roots.iter().find(|r| r.len() == n).map(|r| r.as_ref());
+    }
+    /// caches everything `dft_with_scratch` needs for a transform of length `n`
+    pub fn ensure_len(&mut self, n: usize)
+    {
+        if n <= 1 { return; }
+        if power_of_2(n)
+        {
+            self.ensure_max_power(n.trailing_zeros() as usize);
+            return;
+        }
+        
+        let size = match factorise(n)
+        {
+            Some(_) => n,
+            // bluestein uses the roots of 2n and a power of 2 convolution
+            None =>
+            {
+                self.ensure_max_power(bluestein_power(n));
+                n << 1
+            }
+        };
+        if self.get_roots(size).is_some() { return; }
+        
+        self.roots.push(compute_all_roots(size, self.invert));
+    }
+    /// caches everything a real transform of length `n` needs
+    pub fn ensure_real_len(&mut self, n: usize)
+    {
+        if n <= 1 { return; }
+        if n % 2 == 1
+        {
+            self.ensure_len(n);
+            return;
+        }
+        
+        self.ensure_len(n >> 1);
+        if power_of_2(n)
+        {
+            self.ensure_max_power(n.trailing_zeros() as usize);
+            return;
+        }
+        if self.get_roots(n).is_some() { return; }
+        
+        self.roots.push(compute_all_roots(n, self.invert));
+    }
     pub fn set_invert(&mut self, invert: bool)
     {
         if self.invert == invert { return; }
@@ -44,7 +94,7 @@ impl<T: Float + ConstOne + ConstZero + FloatConst> WCache<T>
         // self.ensure_max_power(power);
         
         // swap polarity of imaginary components
-        for nth in &mut self.nth_roots
+        for nth in self.nth_roots.iter_mut().chain(self.roots.iter_mut())
         {
             for c in nth
             {
@@ -76,21 +126,269 @@ fn compute_nth_roots<T: Float + ConstOne + ConstZero + FloatConst>(n: usize, inv
     return result.into_boxed_slice();
 }
 
+/// all `n` roots, computed directly to avoid accumulating error
+pub(crate) fn compute_all_roots<T: Float + ConstOne + ConstZero + FloatConst>(n: usize, invert: bool) -> Box<[Complex<T>]>
+{
+    let step = match invert
+    {
+        false => T::TAU() / T::from(n).unwrap(),
+        true => -T::TAU() / T::from(n).unwrap()
+    };
+    
+    return (0..n).map(|i| Complex::cis(step * T::from(i).unwrap())).collect();
+}
+
 pub(super) fn power_of_2(n: usize) -> bool
 {
     return (n & (n - 1)) == 0;
 }
 
-pub fn dft<T: Float + ConstOne + ConstZero + FloatConst>(
-    wn: &WCache<T>, y: &mut [Complex<T>])
+/// radix 2, 3 and 5 factors of `n`, none if any other prime divides it
+pub(crate) fn factorise(mut n: usize) -> Option<Vec<usize>>
 {
-    if !power_of_2(y.len())
+    let mut factors = Vec::new();
+    for r in [2, 3, 5]
     {
-        panic!("y must have a power of 2 length");
+        while n % r == 0
+        {
+            factors.push(r);
+            n /= r;
+        }
+    }
+    
+    if n != 1 { return None; }
+    return Some(factors);
+}
+
+/// power of the smallest power of 2 at least `2n - 1`
+pub(crate) fn bluestein_power(n: usize) -> usize
+{
+    return (usize::BITS - ((n << 1) - 1).leading_zeros()) as usize;
+}
+
+/// scratch needed to transform length `n`
+pub fn scratch_len(n: usize) -> usize
+{
+    if n <= 1 { return 0; }
+    if power_of_2(n) || factorise(n).is_some() { return n; }
+    
+    return 2 << bluestein_power(n);
+}
+
+/// `scratch` must be at least `scratch_len(y.len())` long
+pub fn dft_with_scratch<T: Float + ConstOne + ConstZero + FloatConst>(
+    wn: &WCache<T>, y: &mut [Complex<T>], scratch: &mut [Complex<T>])
+{
+    let n = y.len();
+    if n <= 1 { return; }
+    
+    if power_of_2(n)
+    {
+        fft_iterative_scratch(wn, y, n.trailing_zeros() as usize, scratch);
+        // fft_recursive(wn, y, n.trailing_zeros() as usize);
+        return;
+    }
+    
+    match factorise(n)
+    {
+        Some(factors) => fft_mixed_radix(wn, y, &factors, scratch),
+        None => fft_bluestein(wn, y, scratch)
+    }
+}
+
+/// turns the transform of packed pairs in `out[..h]` into the `h + 1` real frequencies
+pub(crate) fn real_unpack<T: Float + ConstOne + ConstZero + FloatConst>(
+    out: &mut [Complex<T>], w: &[Complex<T>])
+{
+    let h = out.len() - 1;
+    let half = T::from(0.5).unwrap();
+    let split = |a: Complex<T>, b: Complex<T>, w: Complex<T>|
+    {
+        let b = b.conj();
+        let even = (a + b) * half;
+        // (a - b) / 2i
+        let odd = (a - b) * Complex::new(T::ZERO, -half);
+        return even + (w * odd);
+    };
+    
+    // w^h = -1
+    let z0 = out[0];
+    out[h] = Complex::new(z0.re - z0.im, T::ZERO);
+    // each pair k, h - k only depends on itself
+    for k in 0..=(h / 2)
+    {
+        let j = (h - k) % h;
+        let a = out[k];
+        let b = out[j];
+        out[k] = split(a, b, w[k]);
+        if j != k { out[j] = split(b, a, w[j]); }
+    }
+}
+
+/// packs the frequencies of a real sequence of length `2 * z.len()` for a transform of `z.len()`,
+/// `w` must be roots in the direction of that transform
+pub(crate) fn real_pack<T: Float + ConstOne + ConstZero + FloatConst>(
+    spectrum: &[Complex<T>], w: &[Complex<T>], z: &mut [Complex<T>])
+{
+    let h = z.len();
+    let get = |k: usize| spectrum.get(k).copied().unwrap_or(Complex::<T>::ZERO);
+    let i = Complex::new(T::ZERO, T::ONE);
+    for (k, z) in z.iter_mut().enumerate()
+    {
+        let a = get(k);
+        let b = get(h - k).conj();
+        *z = (a + b) + (i * (a - b) * w[k]);
+    }
+}
+
+/// `y.len()` must be the product of `factors`, which must be 2, 3 or 5
+pub fn fft_mixed_radix<T: Float + ConstOne + ConstZero + FloatConst>(
+    wn: &WCache<T>, y: &mut [Complex<T>], factors: &[usize], scratch: &mut [Complex<T>])
+{
+    let n = y.len();
+    let computed;
+    let roots = match wn.get_roots(n)
+    {
+        Some(r) => r,
+        None =>
+        {
+            computed = compute_all_roots(n, wn.invert);
+            computed.as_ref()
+        }
+    };
+    
+    mixed_radix_roots(roots, y, factors, scratch);
+}
+
+pub(crate) fn mixed_radix_roots<T: Float + ConstOne + ConstZero + FloatConst>(
+    roots: &[Complex<T>], y: &mut [Complex<T>], factors: &[usize], scratch: &mut [Complex<T>])
+{
+    let input = &mut scratch[..y.len()];
+    input.copy_from_slice(y);
+    mixed_radix_step(input, y, 1, roots, 1, factors);
+}
+
+/// decimation in time, `out` holds the transform of every `stride`th value of `x`
+fn mixed_radix_step<T: Float + ConstOne + ConstZero + FloatConst>(
+    x: &[Complex<T>], out: &mut [Complex<T>], stride: usize, roots: &[Complex<T>], rstep: usize, factors: &[usize])
+{
+    let n = out.len();
+    if n == 1
+    {
+        out[0] = x[0];
+        return;
+    }
+    
+    let r = factors[0];
+    let m = n / r;
+    for p in 0..r
+    {
+        mixed_radix_step(&x[(p * stride)..], &mut out[(p * m)..((p + 1) * m)],
+            stride * r, roots, rstep * r, &factors[1..]);
+    }
+    
+    // combine the r sub transforms, each output k + qm only reads from k + pm
+    let rn = roots.len() / r;
+    let mut t = [Complex::<T>::ZERO; 5];
+    for k in 0..m
+    {
+        for (p, t) in t.iter_mut().enumerate().take(r)
+        {
+            *t = out[(p * m) + k] * roots[p * k * rstep];
+        }
+        for q in 0..r
+        {
+            let mut sum = t[0];
+            for p in 1..r
+            {
+                sum = sum + (t[p] * roots[((p * q) % r) * rn]);
+            }
+            out[k + (q * m)] = sum;
+        }
+    }
+}
+
+/// jk = (j^2 + k^2 - (k - j)^2) / 2, so w^jk = c_j c_k conj(c_(k - j)) with c_j = w^(j^2 / 2)
+pub(crate) fn bluestein_chirp<T: Float + ConstOne + ConstZero + FloatConst>(
+    wn: &WCache<T>, n: usize) -> Box<[Complex<T>]>
+{
+    let n2 = n << 1;
+    let computed;
+    let roots = match wn.get_roots(n2)
+    {
+        Some(r) => r,
+        None =>
+        {
+            computed = compute_all_roots(n2, wn.invert);
+            computed.as_ref()
+        }
+    };
+    
+    return (0..n).map(|j| roots[(j * j) % n2]).collect();
+}
+
+/// transform of the conjugate chirp, wrapped for a circular convolution
+pub(crate) fn bluestein_kernel<T: Float + ConstOne + ConstZero + FloatConst>(
+    wn: &WCache<T>, chirp: &[Complex<T>], scratch: &mut [Complex<T>]) -> Box<[Complex<T>]>
+{
+    let n = chirp.len();
+    let power = bluestein_power(n);
+    let m = 1 << power;
+    
+    let mut b = vec![Complex::<T>::ZERO; m];
+    b[0] = chirp[0].conj();
+    for j in 1..n
+    {
+        b[j] = chirp[j].conj();
+        b[m - j] = b[j];
+    }
+    
+    fft_iterative_scratch(wn, &mut b, power, scratch);
+    return b.into_boxed_slice();
+}
+
+/// any length, as a convolution with a chirp using power of 2 transforms
+pub fn fft_bluestein<T: Float + ConstOne + ConstZero + FloatConst>(
+    wn: &WCache<T>, y: &mut [Complex<T>], scratch: &mut [Complex<T>])
+{
+    let power = bluestein_power(y.len());
+    if wn.nth_roots.len() < power
+    {
+        panic!("roots must be cached with ensure_len");
+    }
+    
+    let chirp = bluestein_chirp(wn, y.len());
+    let kernel = bluestein_kernel(wn, &chirp, scratch);
+    bluestein_convolve(wn, y, &chirp, &kernel, scratch);
+}
+
+/// `scratch` must be at least twice `kernel.len()`
+pub(crate) fn bluestein_convolve<T: Float + ConstOne + ConstZero + FloatConst>(
+    wn: &WCache<T>, y: &mut [Complex<T>], chirp: &[Complex<T>], kernel: &[Complex<T>], scratch: &mut [Complex<T>])
+{
+    let m = kernel.len();
+    let power = m.trailing_zeros() as usize;
+    let (a, scratch) = scratch.split_at_mut(m);
+    
+    a.fill(Complex::<T>::ZERO);
+    for (a, (y, c)) in a.iter_mut().zip(y.iter().zip(chirp))
+    {
+        *a = y * c;
+    }
+    
+    fft_iterative_scratch(wn, a, power, scratch);
+    // inverse by conjugation, so the direction of wn doesn't matter
+    for (a, b) in a.iter_mut().zip(kernel)
+    {
+        *a = (*a * b).conj();
+    }
+    fft_iterative_scratch(wn, a, power, scratch);
+    
+    let s = T::ONE / T::from(m).unwrap();
+    for (y, (a, c)) in y.iter_mut().zip(a.iter().zip(chirp))
+    {
+        *y = a.conj() * c * s;
     }
-    // dft_test(wn, y);
-    fft_iterative_v2(wn, y, (usize::BITS - y.len().leading_zeros() - 1) as usize);
-    // fft_recursive(wn, y, (usize::BITS - y.len().leading_zeros() - 1) as usize);
 }
 
 /// `y.len()`must be a power of 2
@@ -177,15 +475,14 @@ pub fn dft<T: Float + ConstOne + ConstZero + FloatConst>(
 //     }
 // }
 
-/// `y.len()`must be a power of 2
-pub fn fft_iterative_v2<T: Float + ConstOne + ConstZero + FloatConst>(
-    wn: &WCache<T>, y: &mut [Complex<T>], power: usize)
+/// `y.len()`must be a power of 2, `scratch` at least as long
+pub fn fft_iterative_scratch<T: Float + ConstOne + ConstZero + FloatConst>(
+    wn: &WCache<T>, y: &mut [Complex<T>], power: usize, scratch: &mut [Complex<T>])
 {
     let n = y.len();
     
-    let mut buffer = vec![Complex::<T>::ZERO; y.len()];
     let mut din = y;
-    let mut dout: &mut [Complex<T>] = buffer.as_mut();
+    let mut dout: &mut [Complex<T>] = &mut scratch[..n];
     
     let hn = n >> 1;
     let mut hs = hn;
@@ -0,0 +1,87 @@
+use std::fmt::Display;
+
+use num::{Complex, Float};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterKind
+{
+    #[default]
+    LowPass,
+    HighPass,
+    BandPass,
+    Notch
+}
+
+impl FilterKind
+{
+    pub const ALL: [FilterKind; 4] = [FilterKind::LowPass, FilterKind::HighPass, FilterKind::BandPass, FilterKind::Notch];
+    
+    /// whether `width` is used
+    pub fn is_band(&self) -> bool
+    {
+        return matches!(self, FilterKind::BandPass | FilterKind::Notch);
+    }
+}
+
+impl Display for FilterKind
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return f.write_str(match self
+        {
+            FilterKind::LowPass => "Low pass",
+            FilterKind::HighPass => "High pass",
+            FilterKind::BandPass => "Band pass",
+            FilterKind::Notch => "Notch"
+        });
+    }
+}
+
+/// butterworth magnitude response, applied directly to a spectrum.
+/// frequencies are in the same units as `cutoff` and `width`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralFilter<T>
+{
+    pub kind: FilterKind,
+    /// edge of a low or high pass, centre of a band
+    pub cutoff: T,
+    /// width of a band at half power
+    pub width: T,
+    /// steeper roll-off with higher orders
+    pub order: u32
+}
+
+impl<T: Float> SpectralFilter<T>
+{
+    pub fn new(kind: FilterKind, cutoff: T, width: T, order: u32) -> Self
+    {
+        return Self { kind, cutoff, width, order };
+    }
+    
+    pub fn gain(&self, f: T) -> T
+    {
+        let f = f.abs();
+        let cutoff = self.cutoff.max(T::epsilon());
+        let width = self.width.max(T::epsilon());
+        // ratio is 1 at the half power points
+        let ratio = match self.kind
+        {
+            FilterKind::LowPass => f / cutoff,
+            FilterKind::HighPass => cutoff / f,
+            FilterKind::BandPass => ((f * f) - (cutoff * cutoff)) / (f * width),
+            FilterKind::Notch => (f * width) / ((f * f) - (cutoff * cutoff))
+        };
+        let r = ratio.abs().powi(2 * self.order.max(1) as i32);
+        if r.is_nan() { return T::zero(); }
+        
+        return (T::one() + r).sqrt().recip();
+    }
+    /// bin `k` of `spectrum` is at frequency `k * step`
+    pub fn apply(&self, spectrum: &mut [Complex<T>], step: T)
+    {
+        for (k, c) in spectrum.iter_mut().enumerate()
+        {
+            *c = *c * self.gain(T::from(k).unwrap() * step);
+        }
+    }
+}
\ No newline at end of file
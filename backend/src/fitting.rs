@@ -0,0 +1,349 @@
+use std::io::{self, BufRead};
+
+use num::{traits::{float::TotalOrder, ConstOne, ConstZero, FloatConst}, Float, NumCast};
+use zene_structs::Vector2;
+
+use crate::{find_maxima, find_minima, EMEnv, IntoF32, Slit, Wave};
+
+/// minimises `f` from `start`, with the initial simplex spread by `step`.
+/// `progress` is given the iteration and best value, returning false stops early
+pub fn nelder_mead<T, F, P>(mut f: F, start: &[T], step: &[T], max_iter: usize, tolerance: T, mut progress: P) -> (Vec<T>, T)
+    where T: Float + TotalOrder,
+        F: FnMut(&[T]) -> T,
+        P: FnMut(usize, T) -> bool
+{
+    let n = start.len();
+    let half = T::from(0.5).unwrap();
+    let two = T::from(2.0).unwrap();
+    
+    let mut simplex: Vec<(Vec<T>, T)> = Vec::with_capacity(n + 1);
+    simplex.push((start.to_vec(), f(start)));
+    for i in 0..n
+    {
+        let mut p = start.to_vec();
+        p[i] = p[i] + step[i];
+        let v = f(&p);
+        simplex.push((p, v));
+    }
+    
+    let lerp = |a: &[T], b: &[T], t: T| -> Vec<T>
+    {
+        return a.iter().zip(b).map(|(a, b)| *a + ((*b - *a) * t)).collect();
+    };
+    
+    for iter in 0..max_iter
+    {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        
+        let best = simplex[0].1;
+        let worst = simplex[n].1;
+        if !progress(iter, best) { break; }
+        if (worst - best).abs() <= tolerance * (best.abs() + T::epsilon()) { break; }
+        
+        // centroid of all but the worst
+        let mut centre = vec![T::zero(); n];
+        for (p, _) in &simplex[..n]
+        {
+            for (c, v) in centre.iter_mut().zip(p)
+            {
+                *c = *c + *v;
+            }
+        }
+        let scale = T::one() / T::from(n).unwrap();
+        for c in &mut centre
+        {
+            *c = *c * scale;
+        }
+        
+        // reflect
+        let reflect = lerp(&centre, &simplex[n].0, -T::one());
+        let fr = f(&reflect);
+        if fr < best
+        {
+            let expand = lerp(&centre, &simplex[n].0, -two);
+            let fe = f(&expand);
+            simplex[n] = if fe < fr { (expand, fe) } else { (reflect, fr) };
+            continue;
+        }
+        if fr < simplex[n - 1].1
+        {
+            simplex[n] = (reflect, fr);
+            continue;
+        }
+        
+        // contract
+        let contract = match fr < worst
+        {
+            true => lerp(&centre, &simplex[n].0, -half),
+            false => lerp(&centre, &simplex[n].0, half)
+        };
+        let fc = f(&contract);
+        if fc < worst.min(fr)
+        {
+            simplex[n] = (contract, fc);
+            continue;
+        }
+        
+        // shrink towards best
+        let best_p = simplex[0].0.clone();
+        for (p, v) in simplex.iter_mut().skip(1)
+        {
+            *p = lerp(&best_p, p, half);
+            *v = f(p);
+        }
+    }
+    
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    return simplex.swap_remove(0);
+}
+
+/// inverts a small square matrix in place by gauss-jordan elimination, false if singular
+pub fn invert_matrix<T: Float + TotalOrder>(m: &mut [Vec<T>]) -> bool
+{
+    let n = m.len();
+    let mut inv: Vec<Vec<T>> = (0..n).map(|i| (0..n).map(|j| if i == j { T::one() } else { T::zero() }).collect()).collect();
+    
+    for c in 0..n
+    {
+        let pivot = (c..n).max_by(|a, b| m[*a][c].abs().total_cmp(&m[*b][c].abs())).unwrap();
+        if m[pivot][c].is_nan() || m[pivot][c].abs() <= T::epsilon() { return false; }
+        m.swap(c, pivot);
+        inv.swap(c, pivot);
+        
+        let d = T::one() / m[c][c];
+        for j in 0..n
+        {
+            m[c][j] = m[c][j] * d;
+            inv[c][j] = inv[c][j] * d;
+        }
+        
+        for r in 0..n
+        {
+            if r == c { continue; }
+            let f = m[r][c];
+            for j in 0..n
+            {
+                m[r][j] = m[r][j] - (f * m[c][j]);
+                inv[r][j] = inv[r][j] - (f * inv[c][j]);
+            }
+        }
+    }
+    
+    for (row, i) in m.iter_mut().zip(inv)
+    {
+        *row = i;
+    }
+    return true;
+}
+
+/// reads two numeric columns from csv, lines that do not parse (such as headers)
+/// or are not finite are skipped
+pub fn read_csv_columns<T: Float + std::str::FromStr, R: BufRead>(reader: R, x_col: usize, y_col: usize) -> io::Result<Vec<(T, T)>>
+{
+    let mut result = Vec::new();
+    for line in reader.lines()
+    {
+        let line = line?;
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        let x = fields.get(x_col).and_then(|f| f.parse::<T>().ok());
+        let y = fields.get(y_col).and_then(|f| f.parse::<T>().ok());
+        if let (Some(x), Some(y)) = (x, y)
+        {
+            if x.is_finite() && y.is_finite()
+            {
+                result.push((x, y));
+            }
+        }
+    }
+    
+    return Ok(result);
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SlitFit<T: Float>
+{
+    pub count: usize,
+    pub width: T,
+    pub separation: T,
+    /// position along the screen of the central axis, in the units of the measured positions
+    pub offset: T,
+    /// standard errors of width, separation and offset
+    pub width_err: T,
+    pub separation_err: T,
+    pub offset_err: T,
+    /// sum of squared residuals
+    pub residual: T
+}
+
+struct FitModel<'a, T: Float>
+{
+    env: EMEnv<T>,
+    start: T,
+    waves: &'a [Wave<T>],
+    wavelengths: Vec<T>,
+    measured: Vec<T>,
+    model: Vec<T>
+}
+
+impl<'a, T> FitModel<'a, T>
+    where T: Float + ConstOne + ConstZero + FloatConst + IntoF32
+{
+    /// params are width, separation and offset, returns the best scale
+    fn evaluate(&mut self, count: usize, params: &[T]) -> T
+    {
+        let (width, separation, offset) = (params[0], params[1], params[2]);
+        let half = T::from(count - 1).unwrap() * T::from(0.5).unwrap();
+        let slits: Vec<Slit<'_, T>> = (0..count).map(|j|
+        {
+            let x = self.start + offset + ((T::from(j).unwrap() - half) * separation);
+            return Slit::new(width, Vector2::new(x, T::zero()), Vector2::new(T::zero(), T::one()), self.waves);
+        }).collect();
+        
+        self.env.generate_intensity(&slits, &self.wavelengths, &mut self.model);
+        
+        // least squares scale
+        let (my, mm) = self.model.iter().zip(&self.measured)
+            .fold((T::zero(), T::zero()), |(my, mm), (m, y)| (my + (*m * *y), mm + (*m * *m)));
+        if mm <= T::zero() { return T::zero(); }
+        return my / mm;
+    }
+    fn residuals(&mut self, count: usize, params: &[T]) -> Vec<T>
+    {
+        let s = self.evaluate(count, params);
+        return self.model.iter().zip(&self.measured).map(|(m, y)| *y - (s * *m)).collect();
+    }
+    fn cost(&mut self, count: usize, params: &[T]) -> T
+    {
+        if params[0] <= T::zero() || (count > 1 && params[1] <= params[0])
+        {
+            return T::infinity();
+        }
+        return self.residuals(count, params).iter().fold(T::zero(), |a, r| a + (*r * *r));
+    }
+}
+
+/// fits `count` slits of equal width and spacing to an intensity profile.
+/// `measured` is (position along a flat screen, intensity), evenly spaced, in the same units as the wavelengths.
+/// `distance` is from the slits to the screen
+pub fn fit_slits<T>(measured: &[(T, T)], distance: T, waves: &[Wave<T>], max_count: usize) -> Option<SlitFit<T>>
+    where T: Float + ConstOne + ConstZero + FloatConst + IntoF32 + TotalOrder
+{
+    let n = measured.len();
+    if n < 8 || waves.len() == 0 { return None; }
+    
+    let start = measured[0].0;
+    let end = measured[n - 1].0;
+    let intensity: Vec<T> = measured.iter().map(|m| m.1).collect();
+    
+    let mut model = FitModel {
+        env: EMEnv::new(Vector2::new(start, distance), Vector2::new(end, distance)),
+        start,
+        waves,
+        wavelengths: waves.iter().map(|w| w.lambda).collect(),
+        measured: intensity.clone(),
+        model: vec![T::zero(); n]
+    };
+    
+    // amplitude weighted wavelength
+    let total = waves.iter().fold(T::zero(), |a, w| a + w.amplitude);
+    // nothing lit, e.g. a cleared spectrum
+    if total <= T::zero() { return None; }
+    let lambda = waves.iter().fold(T::zero(), |a, w| a + (w.lambda * w.amplitude)) / total;
+    let step = (end - start) / T::from(n - 1).unwrap();
+    
+    // guesses from the fringes
+    let maxima = find_maxima(&intensity, T::from(0.05).unwrap());
+    let centre = maxima.iter().copied().max_by(|a, b| intensity[*a].total_cmp(&intensity[*b]))?;
+    let offset = T::from(centre).unwrap() * step;
+    let spacing = match maxima.len()
+    {
+        0 | 1 => end - start,
+        m => T::from(maxima[m - 1] - maxima[0]).unwrap() * step / T::from(m - 1).unwrap()
+    };
+    // first minimum of the central maximum
+    let minima = find_minima(&intensity, &maxima);
+    let half_width = minima.iter().map(|m| (T::from(*m).unwrap() - T::from(centre).unwrap()).abs() * step)
+        .fold(end - start, |a, b| a.min(b));
+    
+    let mut best: Option<SlitFit<T>> = None;
+    for count in 1..=max_count
+    {
+        let separation = lambda * distance / spacing;
+        let widths = match count
+        {
+            1 => vec![lambda * distance / half_width],
+            _ => vec![separation * T::from(0.5).unwrap(), separation * T::from(0.25).unwrap(), separation * T::from(0.1).unwrap()]
+        };
+        
+        for width in widths
+        {
+            let start_p = [width, separation.max(width * T::from(2.0).unwrap()), offset];
+            let steps = [width * T::from(0.3).unwrap(), start_p[1] * T::from(0.1).unwrap(), step * T::from(4.0).unwrap()];
+            let (p, cost) = nelder_mead(|p| model.cost(count, p), &start_p, &steps,
+                2000, T::from(1e-10).unwrap(), |_, _| true);
+            
+            // prefer fewer slits unless clearly better
+            let better = match &best
+            {
+                None => true,
+                Some(b) if b.count == count => cost < b.residual,
+                Some(b) => cost < b.residual * T::from(0.95).unwrap()
+            };
+            if !better || !cost.is_finite() { continue; }
+            
+            let errors = fit_errors(&mut model, count, &p, cost);
+            best = Some(SlitFit {
+                count,
+                width: p[0],
+                separation: if count > 1 { p[1] } else { T::zero() },
+                offset: start + p[2],
+                width_err: errors[0],
+                separation_err: if count > 1 { errors[1] } else { T::zero() },
+                offset_err: errors[2],
+                residual: cost
+            });
+        }
+    }
+    
+    return best;
+}
+
+/// standard errors from the jacobian of the residuals, cov = s^2 (J^T J)^-1
+fn fit_errors<T>(model: &mut FitModel<'_, T>, count: usize, p: &[T], cost: T) -> [T; 3]
+    where T: Float + ConstOne + ConstZero + FloatConst + IntoF32 + TotalOrder
+{
+    // separation is not used for a single slit
+    let used: Vec<usize> = if count > 1 { vec![0, 1, 2] } else { vec![0, 2] };
+    let m = model.measured.len();
+    let two = T::from(2.0).unwrap();
+    
+    let jacobian: Vec<Vec<T>> = used.iter().map(|i|
+    {
+        let h = (p[*i].abs() * T::from(1e-4).unwrap()).max(T::epsilon().sqrt());
+        let mut a = p.to_vec();
+        let mut b = p.to_vec();
+        a[*i] = a[*i] + h;
+        b[*i] = b[*i] - h;
+        let ra = model.residuals(count, &a);
+        let rb = model.residuals(count, &b);
+        return ra.iter().zip(rb).map(|(a, b)| (*a - b) / (two * h)).collect::<Vec<T>>();
+    }).collect();
+    
+    let k = used.len();
+    let mut jtj: Vec<Vec<T>> = (0..k).map(|i| (0..k).map(|j|
+    {
+        return jacobian[i].iter().zip(&jacobian[j]).fold(T::zero(), |a, (x, y)| a + (*x * *y));
+    }).collect()).collect();
+    
+    let mut result = [T::nan(); 3];
+    // one more degree of freedom for the scale
+    let dof = m as isize - k as isize - 1;
+    if dof <= 0 || !invert_matrix(&mut jtj) { return result; }
+    
+    let s2 = cost / <T as NumCast>::from(dof).unwrap();
+    for (j, i) in used.iter().enumerate()
+    {
+        result[*i] = (jtj[j][j] * s2).abs().sqrt();
+    }
+    return result;
+}
\ No newline at end of file
@@ -3,6 +3,12 @@ use std::f64::consts::FRAC_1_SQRT_2;
 
 pub use crate::fft::*;
 
+mod plan;
+pub use crate::plan::*;
+
+mod radix4;
+pub use crate::radix4::*;
+
 mod diffraction;
 pub use crate::diffraction::*;
 
@@ -18,6 +24,42 @@ pub use crate::em_env::*;
 mod colour;
 pub use crate::colour::*;
 
+mod spectra;
+pub use crate::spectra::*;
+
+mod profile;
+pub use crate::profile::*;
+
+mod analysis;
+pub use crate::analysis::*;
+
+mod fitting;
+pub use crate::fitting::*;
+
+mod propagation;
+pub use crate::propagation::*;
+
+mod window;
+pub use crate::window::*;
+
+mod convolution;
+pub use crate::convolution::*;
+
+mod stft;
+pub use crate::stft::*;
+
+mod expression;
+pub use crate::expression::*;
+
+mod wav;
+pub use crate::wav::*;
+
+mod filter;
+pub use crate::filter::*;
+
+mod cepstrum;
+pub use crate::cepstrum::*;
+
 use num::Complex;
 use num::Float;
 use num::traits::ConstOne;
@@ -26,47 +68,17 @@ use num::traits::FloatConst;
 use num::Zero;
 use zene_structs::{Vector2, Vector3, Vector};
 
-fn next_power_of_2(n: usize) -> u32
+pub fn dft_analysis<T: Float + ConstOne + ConstZero + FloatConst + 'static>(
+    plan: &RealFftPlan<T>, plot: &[T]) -> Vec<Complex<T>>
 {
-    return usize::BITS - n.leading_zeros();
+    return plan.forward(plot);
 }
 
-pub fn dft_analysis<T: Float + ConstOne + ConstZero + FloatConst>(
-    wn: &mut WCache<T>, plot: &[T]) -> Vec<Complex<T>>
+/// `plan.len()` is the size of the plot
+pub fn form_plot<T: Float + ConstOne + ConstZero + FloatConst + 'static>(
+    plan: &RealFftPlan<T>, dft: &[Complex<T>]) -> Vec<T>
 {
-    let power = next_power_of_2(plot.len());
-    let hs = 1 << power;
-    
-    let mut data: Vec<Complex<T>> = RepeatUntil::new(plot, hs << 1)
-        .map(|v| Complex::new(*v, T::ZERO)).collect();
-    
-    wn.ensure_max_power(power as usize + 1);
-    
-    dft(wn, &mut data);
-    data.truncate(hs + 1);
-    return data;
-}
-
-pub fn form_plot<T: Float + ConstOne + ConstZero + FloatConst>(
-    wn: &mut WCache<T>, dft: &[Complex<T>], out_size: usize) -> Vec<Complex<T>>
-{
-    let len = dft.len() - 1;
-    
-    if !power_of_2(len)
-    {
-        panic!("dft must have a power of 2 length");
-    }
-    
-    let power = next_power_of_2(len);
-    let s = T::ONE / T::from(len << 1).unwrap();
-    let mut data: Vec<Complex<T>> = dft.iter().copied().chain(dft.iter().skip(1).rev().skip(1).map(|c| c.conj()))
-        .map(|n| n * s).collect();
-    
-    wn.ensure_max_power(power as usize);
-    
-    fft::dft(wn, &mut data);
-    data.truncate(out_size);
-    return data;
+    return plan.inverse_normalised(dft);
 }
 
 /// stolen function
@@ -155,6 +167,26 @@ pub fn wave_length_colour(wl: f32, gamma: f32) -> Vector3<f32>
     );
 }
 
+/// as `wave_length_colour`, but outside the visible range
+/// wavelengths are given a dim false colour instead of black
+pub fn wave_length_false_colour(wl: f32, gamma: f32) -> Vector3<f32>
+{
+    let (a, b, t) = match wl
+    {
+        380.0..781.0 => return wave_length_colour(wl, gamma),
+        // ultraviolet fades from violet
+        _ if wl < 380.0 => ([0.3, 0.0, 0.4], [0.15, 0.15, 0.25], ((380.0 - wl) / 380.0).clamp(0.0, 1.0)),
+        // infrared fades from deep red
+        _ => ([0.4, 0.05, 0.0], [0.25, 0.15, 0.15], ((wl - 780.0) / 2220.0).clamp(0.0, 1.0))
+    };
+    
+    return Vector3::new(
+        (a[0] + ((b[0] - a[0]) * t)).powf(gamma),
+        (a[1] + ((b[1] - a[1]) * t)).powf(gamma),
+        (a[2] + ((b[2] - a[2]) * t)).powf(gamma)
+    );
+}
+
 pub trait IntoF32
 {
     fn into_f32(self) -> f32;
@@ -0,0 +1,425 @@
+use std::sync::Mutex;
+
+use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
+
+use crate::fft::{bluestein_chirp, bluestein_convolve, bluestein_kernel, compute_all_roots, factorise, mixed_radix_roots, power_of_2, real_pack, real_unpack};
+use crate::{fft_radix4, scratch_len, Radix4, WCache};
+
+#[derive(Debug)]
+enum Algorithm<T>
+{
+    Trivial,
+    Radix4(Radix4<T>),
+    MixedRadix(Vec<usize>),
+    Bluestein
+    {
+        chirp: Box<[Complex<T>]>,
+        kernel: Box<[Complex<T>]>
+    }
+}
+
+fn conj_all<T: Float>(y: &mut [Complex<T>])
+{
+    for c in y
+    {
+        c.im = -c.im;
+    }
+}
+
+/// twiddles and scratch for transforms of one length.
+/// can be shared between threads, the scratch is only used when free
+#[derive(Debug)]
+pub struct FftPlan<T>
+{
+    len: usize,
+    wn: WCache<T>,
+    algorithm: Algorithm<T>,
+    scratch: Mutex<Vec<Complex<T>>>
+}
+
+impl<T: Float + ConstOne + ConstZero + FloatConst + 'static> FftPlan<T>
+{
+    pub fn new(len: usize) -> Self
+    {
+        let mut wn = WCache::new(true);
+        wn.ensure_len(len);
+        let mut scratch = vec![Complex::<T>::ZERO; scratch_len(len)];
+        
+        let algorithm = if len <= 1
+        {
+            Algorithm::Trivial
+        }
+        else if power_of_2(len)
+        {
+            Algorithm::Radix4(Radix4::new(len, true))
+        }
+        else
+        {
+            match factorise(len)
+            {
+                Some(factors) => Algorithm::MixedRadix(factors),
+                None =>
+                {
+                    let chirp = bluestein_chirp(&wn, len);
+                    let kernel = bluestein_kernel(&wn, &chirp, &mut scratch);
+                    Algorithm::Bluestein { chirp, kernel }
+                }
+            }
+        };
+        
+        return Self {
+            len,
+            wn,
+            algorithm,
+            scratch: Mutex::new(scratch)
+        };
+    }
+    pub fn len(&self) -> usize
+    {
+        return self.len;
+    }
+    pub fn is_empty(&self) -> bool
+    {
+        return self.len == 0;
+    }
+    pub fn scratch_len(&self) -> usize
+    {
+        return scratch_len(self.len);
+    }
+    
+    fn with_scratch<F: FnOnce(&mut [Complex<T>])>(&self, f: F)
+    {
+        match self.scratch.try_lock()
+        {
+            Ok(mut scratch) => f(&mut scratch),
+            // in use by another thread
+            Err(_) => f(&mut vec![Complex::<T>::ZERO; self.scratch_len()])
+        }
+    }
+    
+    /// `scratch` must be at least `scratch_len()` long
+    pub fn forward_with_scratch(&self, y: &mut [Complex<T>], scratch: &mut [Complex<T>])
+    {
+        if y.len() != self.len
+        {
+            panic!("plan is for a length of {}", self.len);
+        }
+        
+        match &self.algorithm
+        {
+            Algorithm::Trivial => {},
+            Algorithm::Radix4(twiddles) => fft_radix4(twiddles, y, scratch),
+            Algorithm::MixedRadix(factors) =>
+            {
+                let roots = self.wn.get_roots(self.len).unwrap();
+                mixed_radix_roots(roots, y, factors, scratch);
+            },
+            Algorithm::Bluestein { chirp, kernel } => bluestein_convolve(&self.wn, y, chirp, kernel, scratch)
+        }
+    }
+    /// not normalised, conjugates around the forward transform
+    pub fn inverse_with_scratch(&self, y: &mut [Complex<T>], scratch: &mut [Complex<T>])
+    {
+        conj_all(y);
+        self.forward_with_scratch(y, scratch);
+        conj_all(y);
+    }
+    pub fn forward(&self, y: &mut [Complex<T>])
+    {
+        self.with_scratch(|s| self.forward_with_scratch(y, s));
+    }
+    pub fn inverse(&self, y: &mut [Complex<T>])
+    {
+        self.with_scratch(|s| self.inverse_with_scratch(y, s));
+    }
+    /// inverse scaled by `1 / len`, undoes `forward`
+    pub fn inverse_normalised(&self, y: &mut [Complex<T>])
+    {
+        self.inverse(y);
+        
+        let s = T::ONE / T::from(self.len).unwrap();
+        for v in y
+        {
+            *v = *v * s;
+        }
+    }
+}
+
+/// transforms of real sequences, only the `len / 2 + 1` non negative frequencies are kept
+#[derive(Debug)]
+pub struct RealFftPlan<T>
+{
+    len: usize,
+    /// half the length when even
+    inner: FftPlan<T>,
+    twiddles: Box<[Complex<T>]>,
+    inverse_twiddles: Box<[Complex<T>]>,
+    scratch: Mutex<Vec<Complex<T>>>
+}
+
+impl<T: Float + ConstOne + ConstZero + FloatConst + 'static> RealFftPlan<T>
+{
+    pub fn new(len: usize) -> Self
+    {
+        let even = len % 2 == 0;
+        let inner = FftPlan::new(if even { len >> 1 } else { len });
+        
+        let twiddles: Box<[Complex<T>]> = match even && len > 0
+        {
+            true => compute_all_roots(len, true)[..(len >> 1)].into(),
+            false => Box::new([])
+        };
+        let inverse_twiddles = twiddles.iter().map(|w| w.conj()).collect();
+        let scratch = vec![Complex::<T>::ZERO; inner.len() + inner.scratch_len()];
+        
+        return Self {
+            len,
+            inner,
+            twiddles,
+            inverse_twiddles,
+            scratch: Mutex::new(scratch)
+        };
+    }
+    pub fn len(&self) -> usize
+    {
+        return self.len;
+    }
+    pub fn is_empty(&self) -> bool
+    {
+        return self.len == 0;
+    }
+    /// length of the transform
+    pub fn spectrum_len(&self) -> usize
+    {
+        if self.len == 0 { return 0; }
+        return (self.len >> 1) + 1;
+    }
+    pub fn scratch_len(&self) -> usize
+    {
+        return self.inner.len() + self.inner.scratch_len();
+    }
+    
+    fn with_scratch<F: FnOnce(&mut [Complex<T>])>(&self, f: F)
+    {
+        match self.scratch.try_lock()
+        {
+            Ok(mut scratch) => f(&mut scratch),
+            // in use by another thread
+            Err(_) => f(&mut vec![Complex::<T>::ZERO; self.scratch_len()])
+        }
+    }
+    
+    /// `out` must be `spectrum_len()` long and `scratch` at least `scratch_len()`
+    pub fn forward_with_scratch(&self, x: &[T], out: &mut [Complex<T>], scratch: &mut [Complex<T>])
+    {
+        if x.len() != self.len || out.len() != self.spectrum_len()
+        {
+            panic!("plan is for a length of {}", self.len);
+        }
+        if self.len == 0 { return; }
+        
+        let h = self.len >> 1;
+        let (z, scratch) = scratch.split_at_mut(self.inner.len());
+        if self.len % 2 == 1
+        {
+            for (z, x) in z.iter_mut().zip(x)
+            {
+                *z = Complex::new(*x, T::ZERO);
+            }
+            self.inner.forward_with_scratch(z, scratch);
+            out.copy_from_slice(&z[..=h]);
+            return;
+        }
+        
+        // even samples as real, odd as imaginary, in a transform of n / 2
+        for (o, p) in out.iter_mut().zip(x.chunks_exact(2))
+        {
+            *o = Complex::new(p[0], p[1]);
+        }
+        self.inner.forward_with_scratch(&mut out[..h], scratch);
+        real_unpack(out, &self.twiddles);
+    }
+    /// not normalised, missing frequencies are zero.
+    /// `out` must be `len()` long and `scratch` at least `scratch_len()`
+    pub fn inverse_with_scratch(&self, spectrum: &[Complex<T>], out: &mut [T], scratch: &mut [Complex<T>])
+    {
+        if out.len() != self.len
+        {
+            panic!("plan is for a length of {}", self.len);
+        }
+        if self.len == 0 { return; }
+        
+        let n = self.len;
+        let h = n >> 1;
+        let (z, scratch) = scratch.split_at_mut(self.inner.len());
+        if n % 2 == 1
+        {
+            let get = |k: usize| spectrum.get(k).copied().unwrap_or(Complex::<T>::ZERO);
+            // conjugate mirror for a real output
+            for k in 0..=h
+            {
+                z[k] = get(k);
+                if k > 0 { z[n - k] = get(k).conj(); }
+            }
+            self.inner.inverse_with_scratch(z, scratch);
+            for (o, z) in out.iter_mut().zip(z.iter())
+            {
+                *o = z.re;
+            }
+            return;
+        }
+        
+        real_pack(spectrum, &self.inverse_twiddles, z);
+        self.inner.inverse_with_scratch(z, scratch);
+        
+        // unpack even and odd samples
+        for (o, z) in out.chunks_exact_mut(2).zip(z.iter())
+        {
+            o[0] = z.re;
+            o[1] = z.im;
+        }
+    }
+    pub fn forward_into(&self, x: &[T], out: &mut [Complex<T>])
+    {
+        self.with_scratch(|s| self.forward_with_scratch(x, out, s));
+    }
+    pub fn inverse_into(&self, spectrum: &[Complex<T>], out: &mut [T])
+    {
+        self.with_scratch(|s| self.inverse_with_scratch(spectrum, out, s));
+    }
+    pub fn forward(&self, x: &[T]) -> Vec<Complex<T>>
+    {
+        let mut out = vec![Complex::<T>::ZERO; self.spectrum_len()];
+        self.forward_into(x, &mut out);
+        return out;
+    }
+    pub fn inverse(&self, spectrum: &[Complex<T>]) -> Vec<T>
+    {
+        let mut out = vec![T::ZERO; self.len];
+        self.inverse_into(spectrum, &mut out);
+        return out;
+    }
+    /// inverse scaled by `1 / len`, undoes `forward`
+    pub fn inverse_normalised(&self, spectrum: &[Complex<T>]) -> Vec<T>
+    {
+        let s = T::ONE / T::from(self.len).unwrap();
+        return self.inverse(spectrum).iter().map(|v| *v * s).collect();
+    }
+}
+
+/// transforms of row major data `width` wide and `height` tall
+#[derive(Debug)]
+pub struct FftPlan2D<T>
+{
+    rows: FftPlan<T>,
+    columns: FftPlan<T>,
+    scratch: Mutex<Vec<Complex<T>>>
+}
+
+impl<T: Float + ConstOne + ConstZero + FloatConst + 'static> FftPlan2D<T>
+{
+    pub fn new(width: usize, height: usize) -> Self
+    {
+        let rows = FftPlan::new(width);
+        let columns = FftPlan::new(height);
+        let scratch = vec![Complex::<T>::ZERO; Self::scratch_size(&rows, &columns)];
+        
+        return Self {
+            rows,
+            columns,
+            scratch: Mutex::new(scratch)
+        };
+    }
+    fn scratch_size(rows: &FftPlan<T>, columns: &FftPlan<T>) -> usize
+    {
+        // a column and the scratch of either transform
+        return columns.len() + rows.scratch_len().max(columns.scratch_len());
+    }
+    pub fn width(&self) -> usize
+    {
+        return self.rows.len();
+    }
+    pub fn height(&self) -> usize
+    {
+        return self.columns.len();
+    }
+    pub fn scratch_len(&self) -> usize
+    {
+        return Self::scratch_size(&self.rows, &self.columns);
+    }
+    
+    /// `scratch` must be at least `scratch_len()` long
+    fn transform(&self, data: &mut [Complex<T>], inverse: bool, scratch: &mut [Complex<T>])
+    {
+        let width = self.width();
+        if data.len() != width * self.height()
+        {
+            panic!("plan is for {} by {}", width, self.height());
+        }
+        if data.len() == 0 { return; }
+        
+        let (column, scratch) = scratch.split_at_mut(self.height());
+        for row in data.chunks_exact_mut(width)
+        {
+            match inverse
+            {
+                false => self.rows.forward_with_scratch(row, scratch),
+                true => self.rows.inverse_with_scratch(row, scratch)
+            }
+        }
+        
+        for x in 0..width
+        {
+            for (c, v) in column.iter_mut().zip(data.iter().skip(x).step_by(width))
+            {
+                *c = *v;
+            }
+            match inverse
+            {
+                false => self.columns.forward_with_scratch(column, scratch),
+                true => self.columns.inverse_with_scratch(column, scratch)
+            }
+            for (c, v) in column.iter().zip(data.iter_mut().skip(x).step_by(width))
+            {
+                *v = *c;
+            }
+        }
+    }
+    fn with_scratch<F: FnOnce(&mut [Complex<T>])>(&self, f: F)
+    {
+        match self.scratch.try_lock()
+        {
+            Ok(mut scratch) => f(&mut scratch),
+            // in use by another thread
+            Err(_) => f(&mut vec![Complex::<T>::ZERO; self.scratch_len()])
+        }
+    }
+    pub fn forward_with_scratch(&self, data: &mut [Complex<T>], scratch: &mut [Complex<T>])
+    {
+        self.transform(data, false, scratch);
+    }
+    /// not normalised
+    pub fn inverse_with_scratch(&self, data: &mut [Complex<T>], scratch: &mut [Complex<T>])
+    {
+        self.transform(data, true, scratch);
+    }
+    pub fn forward(&self, data: &mut [Complex<T>])
+    {
+        self.with_scratch(|s| self.transform(data, false, s));
+    }
+    pub fn inverse(&self, data: &mut [Complex<T>])
+    {
+        self.with_scratch(|s| self.transform(data, true, s));
+    }
+    /// inverse scaled by `1 / (width * height)`, undoes `forward`
+    pub fn inverse_normalised(&self, data: &mut [Complex<T>])
+    {
+        self.inverse(data);
+        
+        let s = T::ONE / T::from(data.len()).unwrap();
+        for v in data
+        {
+            *v = *v * s;
+        }
+    }
+}
\ No newline at end of file
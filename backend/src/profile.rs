@@ -0,0 +1,78 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use num::Float;
+use zene_structs::Vector2;
+
+#[derive(Debug, Clone, Default)]
+pub struct ScreenProfile<T: Float>
+{
+    pub wavelengths: Vec<T>,
+    /// distance from the first end of the screen
+    pub position: Vec<T>,
+    pub points: Vec<Vector2<T>>,
+    pub total: Vec<T>,
+    /// intensity of each wavelength at each sample, empty if not generated
+    pub per_wave: Vec<Vec<T>>
+}
+
+impl<T: Float> ScreenProfile<T>
+{
+    pub fn new(wavelengths: Vec<T>, count: usize) -> Self
+    {
+        return Self {
+            wavelengths,
+            position: Vec::with_capacity(count),
+            points: Vec::with_capacity(count),
+            total: Vec::with_capacity(count),
+            per_wave: Vec::new()
+        };
+    }
+    
+    pub fn len(&self) -> usize
+    {
+        return self.total.len();
+    }
+    
+    /// `extra` columns are written between the sample position and intensities,
+    /// each must have a value for every sample
+    pub fn write_csv<W: Write>(&self, out: &mut W, extra: &[(String, Vec<T>)]) -> io::Result<()>
+        where T: Display
+    {
+        write!(out, "position,x,y")?;
+        for (name, _) in extra
+        {
+            write!(out, ",{name}")?;
+        }
+        write!(out, ",total")?;
+        if self.per_wave.len() != 0
+        {
+            for l in &self.wavelengths
+            {
+                write!(out, ",{l}nm")?;
+            }
+        }
+        writeln!(out)?;
+        
+        for i in 0..self.len()
+        {
+            let p = self.points[i];
+            write!(out, "{},{},{}", self.position[i], p.x, p.y)?;
+            for (_, values) in extra
+            {
+                write!(out, ",{}", values[i])?;
+            }
+            write!(out, ",{}", self.total[i])?;
+            if let Some(waves) = self.per_wave.get(i)
+            {
+                for v in waves
+                {
+                    write!(out, ",{v}")?;
+                }
+            }
+            writeln!(out)?;
+        }
+        
+        return Ok(());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,139 @@
+use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
+
+use crate::{FftPlan, FftPlan2D};
+
+/// spatial frequency of bin `k` of an `n` point transform of samples `dx` apart
+pub fn spatial_frequency<T: Float>(k: usize, n: usize, dx: T) -> T
+{
+    let scale = T::one() / (T::from(n).unwrap() * dx);
+    // upper half are negative frequencies
+    if k <= n / 2
+    {
+        return T::from(k).unwrap() * scale;
+    }
+    return -T::from(n - k).unwrap() * scale;
+}
+
+/// angular spectrum transfer function over `z` for squared spatial frequency `f2`
+/// and squared wave number `1 / lambda^2`, evanescent waves decay
+pub fn transfer<T: Float + FloatConst>(f2: T, rl2: T, z: T) -> Complex<T>
+{
+    let d = rl2 - f2;
+    if d >= T::zero()
+    {
+        return Complex::from_polar(T::one(), T::TAU() * z * d.sqrt());
+    }
+    return Complex::new((-T::TAU() * z * (-d).sqrt()).exp(), T::zero());
+}
+
+/// propagates a field sampled every `dx` across the aperture a distance `z`,
+/// the field is periodic so it should be padded to stop light wrapping around
+pub fn propagate_1d<T>(plan: &FftPlan<T>, field: &mut [Complex<T>], dx: T, wavelength: T, z: T)
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let n = field.len();
+    let rl2 = (T::ONE / wavelength).powi(2);
+    
+    plan.forward(field);
+    for (k, c) in field.iter_mut().enumerate()
+    {
+        let fx = spatial_frequency(k, n, dx);
+        *c = *c * transfer(fx * fx, rl2, z);
+    }
+    plan.inverse_normalised(field);
+}
+
+/// position of sample `k` of an `n` point aperture sampled every `dx`, the upper half wrap round below zero
+fn sample_position<T: Float>(k: usize, n: usize, dx: T) -> T
+{
+    if k <= n / 2
+    {
+        return T::from(k).unwrap() * dx;
+    }
+    return -T::from(n - k).unwrap() * dx;
+}
+
+/// adds a slit from `left` to `right` to an aperture sampled every `dx`.
+/// each sample gets the fraction of the slit over it, so the aperture's spectrum gains `value` at zero frequency
+pub fn add_slit<T: Float>(field: &mut [Complex<T>], dx: T, left: T, right: T, value: Complex<T>)
+{
+    let n = field.len() as isize;
+    let width = right - left;
+    if n == 0 || width.is_nan() || width <= T::zero() { return; }
+    
+    let half = T::from(0.5).unwrap();
+    // sample i covers (i - 0.5)dx to (i + 0.5)dx
+    let a = ((left / dx) + half).floor().to_isize().unwrap();
+    let b = ((right / dx) + half).floor().to_isize().unwrap();
+    for i in a..=b
+    {
+        let x = T::from(i).unwrap();
+        let lo = left.max((x - half) * dx);
+        let hi = right.min((x + half) * dx);
+        if hi > lo
+        {
+            let f = &mut field[i.rem_euclid(n) as usize];
+            *f = *f + (value * ((hi - lo) / width));
+        }
+    }
+}
+
+/// far field spectrum of an aperture from `add_slit` with the screen `z` away.
+/// bin `k` is the light leaving at `spatial_frequency(k, n, dx)`, the sine of its angle over the wavelength.
+/// the on axis Fresnel phase is applied first, so slits spread further than the far field allows still line up
+pub fn far_field<T>(plan: &FftPlan<T>, field: &mut [Complex<T>], dx: T, wavelength: T, z: T)
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let n = field.len();
+    let chirp = T::PI() / (wavelength * z);
+    for (k, c) in field.iter_mut().enumerate()
+    {
+        let x = sample_position(k, n, dx);
+        *c = *c * Complex::from_polar(T::ONE, chirp * x * x);
+    }
+    
+    plan.forward(field);
+    for (k, c) in field.iter_mut().enumerate()
+    {
+        // undo spreading each slit over whole samples
+        let a = T::PI() * spatial_frequency(k, n, dx) * dx;
+        if !a.is_zero()
+        {
+            *c = *c * (a / a.sin());
+        }
+    }
+}
+
+/// linear interpolation of a spectrum from `far_field` at spatial frequency `f`
+pub fn sample_far_field<T: Float>(spectrum: &[Complex<T>], dx: T, f: T) -> Complex<T>
+{
+    let n = spectrum.len();
+    let u = f * T::from(n).unwrap() * dx;
+    let l = u.floor();
+    let i = l.to_isize().unwrap().rem_euclid(n as isize) as usize;
+    
+    let a = spectrum[i];
+    let b = spectrum[(i + 1) % n];
+    return a + ((b - a) * (u - l));
+}
+
+/// `propagate_1d` for a row major field sampled every `dx` along rows and `dy` down columns
+pub fn propagate_2d<T>(plan: &FftPlan2D<T>, field: &mut [Complex<T>], dx: T, dy: T, wavelength: T, z: T)
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let width = plan.width();
+    let height = plan.height();
+    let rl2 = (T::ONE / wavelength).powi(2);
+    
+    plan.forward(field);
+    for (j, row) in field.chunks_exact_mut(width).enumerate()
+    {
+        let fy = spatial_frequency(j, height, dy);
+        for (k, c) in row.iter_mut().enumerate()
+        {
+            let fx = spatial_frequency(k, width, dx);
+            *c = *c * transfer((fx * fx) + (fy * fy), rl2, z);
+        }
+    }
+    plan.inverse_normalised(field);
+}
\ No newline at end of file
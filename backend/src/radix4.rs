@@ -0,0 +1,412 @@
+use std::mem::swap;
+
+use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
+
+use crate::fft::{compute_all_roots, power_of_2};
+
+/// twiddles of one radix 4 stage of length `n`, for p < n / 4
+#[derive(Debug, Clone)]
+struct Stage<T>
+{
+    w1: Box<[Complex<T>]>,
+    w2: Box<[Complex<T>]>,
+    w3: Box<[Complex<T>]>
+}
+
+/// twiddles for a radix 4 stockham transform of a power of 2 length,
+/// odd powers finish with a radix 2 stage
+#[derive(Debug, Clone)]
+pub struct Radix4<T>
+{
+    len: usize,
+    stages: Vec<Stage<T>>,
+    /// fourth root of unity in the direction of the transform
+    rot: Complex<T>
+}
+
+impl<T: Float + ConstOne + ConstZero + FloatConst> Radix4<T>
+{
+    /// `invert` as for `WCache`, true is the forward transform
+    pub fn new(len: usize, invert: bool) -> Self
+    {
+        if len == 0 || !power_of_2(len)
+        {
+            panic!("radix 4 length must be a power of 2");
+        }
+        
+        let mut stages = Vec::new();
+        let mut n = len;
+        while n >= 4
+        {
+            let roots = compute_all_roots::<T>(n, invert);
+            let n1 = n >> 2;
+            stages.push(Stage {
+                w1: (0..n1).map(|p| roots[p]).collect(),
+                w2: (0..n1).map(|p| roots[2 * p]).collect(),
+                w3: (0..n1).map(|p| roots[3 * p]).collect()
+            });
+            n = n1;
+        }
+        
+        let rot = match invert
+        {
+            true => Complex::new(T::ZERO, -T::ONE),
+            false => Complex::new(T::ZERO, T::ONE)
+        };
+        return Self { len, stages, rot };
+    }
+    pub fn len(&self) -> usize
+    {
+        return self.len;
+    }
+    pub fn is_empty(&self) -> bool
+    {
+        return self.len == 0;
+    }
+}
+
+/// one butterfly reading `src[q + s(p + k n1)]` and writing `dst[q + s(4p + k)]`,
+/// where n1 is the stage's quarter length
+#[inline(always)]
+unsafe fn butterfly<T: Float>(src: *const Complex<T>, dst: *mut Complex<T>,
+    s: usize, p: usize, q: usize, stage: &Stage<T>, rot: Complex<T>)
+{
+    let n1 = stage.w1.len();
+    let i = q + (s * p);
+    let sn = s * n1;
+    let a = *src.add(i);
+    let b = *src.add(i + sn);
+    let c = *src.add(i + (2 * sn));
+    let d = *src.add(i + (3 * sn));
+    
+    let apc = a + c;
+    let amc = a - c;
+    let bpd = b + d;
+    let jbmd = rot * (b - d);
+    
+    let o = q + (s * (p << 2));
+    *dst.add(o) = apc + bpd;
+    *dst.add(o + s) = *stage.w1.get_unchecked(p) * (amc + jbmd);
+    *dst.add(o + (2 * s)) = *stage.w2.get_unchecked(p) * (apc - bpd);
+    *dst.add(o + (3 * s)) = *stage.w3.get_unchecked(p) * (amc - jbmd);
+}
+
+/// `x` and `y` must both hold `plan.len()` values, the result is left in `x`
+unsafe fn radix4_scalar<T: Float>(plan: &Radix4<T>, x: *mut Complex<T>, y: *mut Complex<T>)
+{
+    let mut src = x;
+    let mut dst = y;
+    let mut n = plan.len;
+    let mut s = 1;
+    
+    for stage in &plan.stages
+    {
+        let n1 = n >> 2;
+        for p in 0..n1
+        {
+            for q in 0..s
+            {
+                butterfly(src, dst, s, p, q, stage, plan.rot);
+            }
+        }
+        
+        swap(&mut src, &mut dst);
+        n = n1;
+        s <<= 2;
+    }
+    
+    if n == 2
+    {
+        for q in 0..s
+        {
+            let a = *src.add(q);
+            let b = *src.add(q + s);
+            *dst.add(q) = a + b;
+            *dst.add(q + s) = a - b;
+        }
+        swap(&mut src, &mut dst);
+    }
+    
+    if src != x
+    {
+        std::ptr::copy_nonoverlapping(src, x, plan.len);
+    }
+}
+
+/// scalar only, for comparison with the simd kernels
+pub fn fft_radix4_scalar<T: Float>(plan: &Radix4<T>, y: &mut [Complex<T>], scratch: &mut [Complex<T>])
+{
+    if y.len() != plan.len || scratch.len() < plan.len
+    {
+        panic!("plan is for a length of {}", plan.len);
+    }
+    
+    unsafe
+    {
+        radix4_scalar(plan, y.as_mut_ptr(), scratch.as_mut_ptr());
+    }
+}
+
+/// uses simd for f32 and f64 when the cpu supports it,
+/// `scratch` must be at least `plan.len()` long
+pub fn fft_radix4<T: Float + 'static>(plan: &Radix4<T>, y: &mut [Complex<T>], scratch: &mut [Complex<T>])
+{
+    if y.len() != plan.len || scratch.len() < plan.len
+    {
+        panic!("plan is for a length of {}", plan.len);
+    }
+    
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::any::TypeId;
+        
+        if is_x86_feature_detected!("sse3")
+        {
+            // the casts only change T to the type it already is
+            if TypeId::of::<T>() == TypeId::of::<f64>()
+            {
+                unsafe
+                {
+                    x86::radix4_f64(&*(plan as *const Radix4<T> as *const Radix4<f64>),
+                        y.as_mut_ptr() as *mut Complex<f64>, scratch.as_mut_ptr() as *mut Complex<f64>);
+                }
+                return;
+            }
+            if TypeId::of::<T>() == TypeId::of::<f32>()
+            {
+                unsafe
+                {
+                    x86::radix4_f32(&*(plan as *const Radix4<T> as *const Radix4<f32>),
+                        y.as_mut_ptr() as *mut Complex<f32>, scratch.as_mut_ptr() as *mut Complex<f32>);
+                }
+                return;
+            }
+        }
+    }
+    
+    unsafe
+    {
+        radix4_scalar(plan, y.as_mut_ptr(), scratch.as_mut_ptr());
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86
+{
+    use std::arch::x86_64::*;
+    use std::mem::swap;
+    
+    use num::Complex;
+    
+    use super::{butterfly, Radix4};
+    
+    /// one complex per register
+    #[inline(always)]
+    unsafe fn mul_pd(a: __m128d, b: __m128d) -> __m128d
+    {
+        let ar = _mm_unpacklo_pd(a, a);
+        let ai = _mm_unpackhi_pd(a, a);
+        let bs = _mm_shuffle_pd(b, b, 1);
+        // (ar br - ai bi, ar bi + ai br)
+        return _mm_addsub_pd(_mm_mul_pd(ar, b), _mm_mul_pd(ai, bs));
+    }
+    
+    /// two complex per register
+    #[inline(always)]
+    unsafe fn mul_ps(a: __m128, b: __m128) -> __m128
+    {
+        let ar = _mm_moveldup_ps(a);
+        let ai = _mm_movehdup_ps(a);
+        let bs = _mm_shuffle_ps(b, b, 0b10_11_00_01);
+        return _mm_addsub_ps(_mm_mul_ps(ar, b), _mm_mul_ps(ai, bs));
+    }
+    
+    /// the same complex in both halves
+    #[inline(always)]
+    unsafe fn load_dup(c: *const Complex<f32>) -> __m128
+    {
+        return _mm_castpd_ps(_mm_loaddup_pd(c as *const f64));
+    }
+    
+    #[target_feature(enable = "sse3")]
+    pub(super) unsafe fn radix4_f64(plan: &Radix4<f64>, x: *mut Complex<f64>, y: *mut Complex<f64>)
+    {
+        let mut src = x as *mut f64;
+        let mut dst = y as *mut f64;
+        let rot = _mm_set_pd(plan.rot.im, plan.rot.re);
+        let mut n = plan.len;
+        let mut s = 1;
+        
+        for stage in &plan.stages
+        {
+            let n1 = n >> 2;
+            // offsets are in f64, two per complex
+            let sn = (s * n1) << 1;
+            for p in 0..n1
+            {
+                let w1 = _mm_loadu_pd(stage.w1.as_ptr().add(p) as *const f64);
+                let w2 = _mm_loadu_pd(stage.w2.as_ptr().add(p) as *const f64);
+                let w3 = _mm_loadu_pd(stage.w3.as_ptr().add(p) as *const f64);
+                for q in 0..s
+                {
+                    let i = (q + (s * p)) << 1;
+                    let a = _mm_loadu_pd(src.add(i));
+                    let b = _mm_loadu_pd(src.add(i + sn));
+                    let c = _mm_loadu_pd(src.add(i + (2 * sn)));
+                    let d = _mm_loadu_pd(src.add(i + (3 * sn)));
+                    
+                    let apc = _mm_add_pd(a, c);
+                    let amc = _mm_sub_pd(a, c);
+                    let bpd = _mm_add_pd(b, d);
+                    let jbmd = mul_pd(rot, _mm_sub_pd(b, d));
+                    
+                    let o = (q + (s * (p << 2))) << 1;
+                    _mm_storeu_pd(dst.add(o), _mm_add_pd(apc, bpd));
+                    _mm_storeu_pd(dst.add(o + (2 * s)), mul_pd(w1, _mm_add_pd(amc, jbmd)));
+                    _mm_storeu_pd(dst.add(o + (4 * s)), mul_pd(w2, _mm_sub_pd(apc, bpd)));
+                    _mm_storeu_pd(dst.add(o + (6 * s)), mul_pd(w3, _mm_sub_pd(amc, jbmd)));
+                }
+            }
+            
+            swap(&mut src, &mut dst);
+            n = n1;
+            s <<= 2;
+        }
+        
+        if n == 2
+        {
+            for q in 0..s
+            {
+                let a = _mm_loadu_pd(src.add(q << 1));
+                let b = _mm_loadu_pd(src.add((q + s) << 1));
+                _mm_storeu_pd(dst.add(q << 1), _mm_add_pd(a, b));
+                _mm_storeu_pd(dst.add((q + s) << 1), _mm_sub_pd(a, b));
+            }
+            swap(&mut src, &mut dst);
+        }
+        
+        if src != x as *mut f64
+        {
+            std::ptr::copy_nonoverlapping(src, x as *mut f64, plan.len << 1);
+        }
+    }
+    
+    #[target_feature(enable = "sse3")]
+    pub(super) unsafe fn radix4_f32(plan: &Radix4<f32>, x: *mut Complex<f32>, y: *mut Complex<f32>)
+    {
+        let mut src = x as *mut f32;
+        let mut dst = y as *mut f32;
+        let rot = load_dup(&plan.rot);
+        let mut n = plan.len;
+        let mut s = 1;
+        
+        for stage in &plan.stages
+        {
+            let n1 = n >> 2;
+            // offsets are in f32, two per complex
+            let sn = (s * n1) << 1;
+            if s == 1
+            {
+                // neighbouring p share a register, their twiddles are contiguous
+                let mut p = 0;
+                while p + 1 < n1
+                {
+                    let w1 = _mm_loadu_ps(stage.w1.as_ptr().add(p) as *const f32);
+                    let w2 = _mm_loadu_ps(stage.w2.as_ptr().add(p) as *const f32);
+                    let w3 = _mm_loadu_ps(stage.w3.as_ptr().add(p) as *const f32);
+                    
+                    let i = p << 1;
+                    let a = _mm_loadu_ps(src.add(i));
+                    let b = _mm_loadu_ps(src.add(i + sn));
+                    let c = _mm_loadu_ps(src.add(i + (2 * sn)));
+                    let d = _mm_loadu_ps(src.add(i + (3 * sn)));
+                    
+                    let apc = _mm_add_ps(a, c);
+                    let amc = _mm_sub_ps(a, c);
+                    let bpd = _mm_add_ps(b, d);
+                    let jbmd = mul_ps(rot, _mm_sub_ps(b, d));
+                    
+                    // outputs of p and p + 1 are 4 complex apart
+                    let o = p << 3;
+                    let out = [
+                        _mm_add_ps(apc, bpd),
+                        mul_ps(w1, _mm_add_ps(amc, jbmd)),
+                        mul_ps(w2, _mm_sub_ps(apc, bpd)),
+                        mul_ps(w3, _mm_sub_ps(amc, jbmd))
+                    ];
+                    for (k, v) in out.iter().enumerate()
+                    {
+                        // each half is one complex, stored as a double
+                        _mm_storel_pd(dst.add(o + (k << 1)) as *mut f64, _mm_castps_pd(*v));
+                        _mm_storeh_pd(dst.add(o + 8 + (k << 1)) as *mut f64, _mm_castps_pd(*v));
+                    }
+                    p += 2;
+                }
+                if p < n1
+                {
+                    butterfly(src as *const Complex<f32>, dst as *mut Complex<f32>, s, p, 0, stage, plan.rot);
+                }
+            }
+            else
+            {
+                // s is a power of 4, so q pairs up
+                for p in 0..n1
+                {
+                    let w1 = load_dup(stage.w1.as_ptr().add(p));
+                    let w2 = load_dup(stage.w2.as_ptr().add(p));
+                    let w3 = load_dup(stage.w3.as_ptr().add(p));
+                    for q in (0..s).step_by(2)
+                    {
+                        let i = (q + (s * p)) << 1;
+                        let a = _mm_loadu_ps(src.add(i));
+                        let b = _mm_loadu_ps(src.add(i + sn));
+                        let c = _mm_loadu_ps(src.add(i + (2 * sn)));
+                        let d = _mm_loadu_ps(src.add(i + (3 * sn)));
+                        
+                        let apc = _mm_add_ps(a, c);
+                        let amc = _mm_sub_ps(a, c);
+                        let bpd = _mm_add_ps(b, d);
+                        let jbmd = mul_ps(rot, _mm_sub_ps(b, d));
+                        
+                        let o = (q + (s * (p << 2))) << 1;
+                        _mm_storeu_ps(dst.add(o), _mm_add_ps(apc, bpd));
+                        _mm_storeu_ps(dst.add(o + (2 * s)), mul_ps(w1, _mm_add_ps(amc, jbmd)));
+                        _mm_storeu_ps(dst.add(o + (4 * s)), mul_ps(w2, _mm_sub_ps(apc, bpd)));
+                        _mm_storeu_ps(dst.add(o + (6 * s)), mul_ps(w3, _mm_sub_ps(amc, jbmd)));
+                    }
+                }
+            }
+            
+            swap(&mut src, &mut dst);
+            n = n1;
+            s <<= 2;
+        }
+        
+        if n == 2
+        {
+            if s == 1
+            {
+                let a = *(src as *const Complex<f32>);
+                let b = *(src as *const Complex<f32>).add(1);
+                *(dst as *mut Complex<f32>) = a + b;
+                *(dst as *mut Complex<f32>).add(1) = a - b;
+            }
+            else
+            {
+                for q in (0..s).step_by(2)
+                {
+                    let a = _mm_loadu_ps(src.add(q << 1));
+                    let b = _mm_loadu_ps(src.add((q + s) << 1));
+                    _mm_storeu_ps(dst.add(q << 1), _mm_add_ps(a, b));
+                    _mm_storeu_ps(dst.add((q + s) << 1), _mm_sub_ps(a, b));
+                }
+            }
+            swap(&mut src, &mut dst);
+        }
+        
+        if src != x as *mut f32
+        {
+            std::ptr::copy_nonoverlapping(src, x as *mut f32, plan.len << 1);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,130 @@
+use num::Float;
+
+/// second radiation constant hc/k, in nm K
+const C2: f64 = 1.438776877e7;
+
+/// (wavelength nm, relative intensity)
+pub const MERCURY_LINES: [(f64, f64); 5] = [
+    (404.656, 0.4),
+    (435.833, 1.0),
+    (546.074, 1.0),
+    (576.960, 0.3),
+    (579.066, 0.3)
+];
+pub const SODIUM_LINES: [(f64, f64); 4] = [
+    (568.820, 0.05),
+    (588.995, 1.0),
+    (589.592, 0.5),
+    (615.424, 0.05)
+];
+// balmer series
+pub const HYDROGEN_LINES: [(f64, f64); 4] = [
+    (410.174, 0.1),
+    (434.047, 0.2),
+    (486.133, 0.4),
+    (656.279, 1.0)
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceSpectrum<T: Float>
+{
+    /// temperature in K
+    Blackbody(T),
+    /// laser lines in nm, all of equal intensity
+    Laser(Vec<T>),
+    Mercury,
+    Sodium,
+    Hydrogen
+}
+
+/// relative spectral radiance of a blackbody, `lambda` in nm
+pub fn planck<T: Float>(lambda: T, temperature: T) -> T
+{
+    let c2 = T::from(C2).unwrap();
+    let l5 = lambda.powi(5);
+    return T::one() / (l5 * ((c2 / (lambda * temperature)).exp() - T::one()));
+}
+
+fn gaussian<T: Float>(x: T, centre: T, fwhm: T) -> T
+{
+    // fwhm = 2 sqrt(2 ln 2) sigma
+    let sigma = fwhm / T::from(2.354820045).unwrap();
+    let d = (x - centre) / sigma;
+    return (-(d * d) / T::from(2.0).unwrap()).exp();
+}
+
+impl<T: Float> SourceSpectrum<T>
+{
+    fn for_lines<F: FnMut(T, T)>(&self, mut f: F)
+    {
+        let lines: &[(f64, f64)] = match self
+        {
+            SourceSpectrum::Blackbody(_) => return,
+            SourceSpectrum::Laser(ls) =>
+            {
+                for l in ls
+                {
+                    f(*l, T::one());
+                }
+                return;
+            },
+            SourceSpectrum::Mercury => &MERCURY_LINES,
+            SourceSpectrum::Sodium => &SODIUM_LINES,
+            SourceSpectrum::Hydrogen => &HYDROGEN_LINES
+        };
+        
+        for (l, i) in lines
+        {
+            f(T::from(*l).unwrap(), T::from(*i).unwrap());
+        }
+    }
+    
+    /// relative spectral intensity at `lambda` in nm,
+    /// lines are gaussian with full width at half maximum `line_width` in nm
+    pub fn intensity(&self, lambda: T, line_width: T) -> T
+    {
+        if let SourceSpectrum::Blackbody(t) = self
+        {
+            return planck(lambda, *t);
+        }
+        
+        let mut total = T::zero();
+        self.for_lines(|l, i| total = total + (i * gaussian(lambda, l, line_width)));
+        return total;
+    }
+    
+    /// amplitudes at each of `lambdas`, normalised so the largest is 1.
+    /// lines narrower than the sampling always reach their nearest sample
+    pub fn sample(&self, lambdas: &[T], line_width: T) -> Vec<T>
+    {
+        let mut result: Vec<T> = lambdas.iter().map(|l| self.intensity(*l, line_width)).collect();
+        if result.len() == 0 { return result; }
+        
+        self.for_lines(|l, i|
+        {
+            let mut close = T::infinity();
+            let mut index = 0;
+            for (j, x) in lambdas.iter().enumerate()
+            {
+                let d = (*x - l).abs();
+                if d < close
+                {
+                    close = d;
+                    index = j;
+                }
+            }
+            
+            result[index] = result[index].max(i);
+        });
+        
+        let max = result.iter().fold(T::zero(), |a, b| a.max(*b));
+        if max <= T::zero() { return result; }
+        
+        // amplitude is the root of intensity
+        for v in &mut result
+        {
+            *v = (*v / max).sqrt();
+        }
+        return result;
+    }
+}
\ No newline at end of file
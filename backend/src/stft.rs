@@ -0,0 +1,50 @@
+use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
+
+use crate::RealFftPlan;
+
+/// frames starting every `hop` samples until a signal of `len` is covered
+pub fn frame_count(len: usize, frame: usize, hop: usize) -> usize
+{
+    if len == 0 || frame == 0 || hop == 0 { return 0; }
+    if len <= frame { return 1; }
+    
+    return (len - frame).div_ceil(hop) + 1;
+}
+
+/// smallest hop that fits a signal of `len` into at most `max` frames
+pub fn hop_for_frames(len: usize, frame: usize, max: usize) -> usize
+{
+    if len <= frame || max < 2 { return frame.max(1); }
+    
+    return (len - frame).div_ceil(max - 1).max(1);
+}
+
+/// short time fourier transform, the spectrum of each `plan.len()` frame multiplied by `window`.
+/// frames start every `hop` samples and are zero padded past the end
+pub fn stft<T>(plan: &RealFftPlan<T>, window: &[T], signal: &[T], hop: usize) -> Vec<Vec<Complex<T>>>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let frame = plan.len();
+    let count = frame_count(signal.len(), frame, hop);
+    let mut buffer = vec![T::ZERO; frame];
+    
+    return (0..count).map(|i|
+    {
+        let start = i * hop;
+        for (j, b) in buffer.iter_mut().enumerate()
+        {
+            let v = signal.get(start + j).copied().unwrap_or(T::ZERO);
+            *b = v * window.get(j).copied().unwrap_or(T::ONE);
+        }
+        return plan.forward(&buffer);
+    }).collect();
+}
+
+/// magnitudes of `stft`, indexed by frame then bin
+pub fn spectrogram<T>(plan: &RealFftPlan<T>, window: &[T], signal: &[T], hop: usize) -> Vec<Vec<T>>
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    return stft(plan, window, signal, hop).iter()
+        .map(|f| f.iter().map(|c| c.norm()).collect())
+        .collect();
+}
\ No newline at end of file
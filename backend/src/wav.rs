@@ -0,0 +1,239 @@
+use std::io::{self, Read, Write};
+
+use crate::correlate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat
+{
+    Int16,
+    Int24,
+    Float32
+}
+
+impl SampleFormat
+{
+    fn bytes(&self) -> usize
+    {
+        return match self
+        {
+            SampleFormat::Int16 => 2,
+            SampleFormat::Int24 => 3,
+            SampleFormat::Float32 => 4
+        };
+    }
+    fn tag(&self) -> u16
+    {
+        return match self
+        {
+            SampleFormat::Float32 => 3,
+            _ => 1
+        };
+    }
+}
+
+/// audio with interleaved channels, samples are in -1 to 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wav
+{
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub format: SampleFormat,
+    pub data: Vec<f32>
+}
+
+fn invalid(message: &str) -> io::Error
+{
+    return io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+}
+
+fn u16_at(b: &[u8], i: usize) -> u16
+{
+    return u16::from_le_bytes([b[i], b[i + 1]]);
+}
+fn u32_at(b: &[u8], i: usize) -> u32
+{
+    return u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+}
+
+impl Wav
+{
+    pub fn new(sample_rate: u32, channels: u16, format: SampleFormat, data: Vec<f32>) -> Self
+    {
+        return Self { sample_rate, channels, format, data };
+    }
+    /// samples per channel
+    pub fn len(&self) -> usize
+    {
+        return self.data.len() / (self.channels.max(1) as usize);
+    }
+    pub fn is_empty(&self) -> bool
+    {
+        return self.len() == 0;
+    }
+    /// channels averaged
+    pub fn mono(&self) -> Vec<f32>
+    {
+        let c = self.channels.max(1) as usize;
+        return self.data.chunks_exact(c).map(|f| f.iter().sum::<f32>() / (c as f32)).collect();
+    }
+    
+    /// PCM 16 or 24 bit integer, or 32 bit float
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Wav>
+    {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE"
+        {
+            return Err(invalid("not a wav file"));
+        }
+        
+        let mut format = None;
+        let mut at = 12;
+        while at + 8 <= bytes.len()
+        {
+            let id = &bytes[at..at + 4];
+            let size = u32_at(&bytes, at + 4) as usize;
+            let body = at + 8;
+            // some writers leave the data size unset
+            let end = body.saturating_add(size).min(bytes.len());
+            
+            if id == b"fmt "
+            {
+                if end - body < 16 { return Err(invalid("short format chunk")); }
+                
+                let mut tag = u16_at(&bytes, body);
+                let channels = u16_at(&bytes, body + 2);
+                let sample_rate = u32_at(&bytes, body + 4);
+                let bits = u16_at(&bytes, body + 14);
+                // extensible, the sub format starts with the tag
+                if tag == 0xFFFE && end - body >= 26
+                {
+                    tag = u16_at(&bytes, body + 24);
+                }
+                
+                let sf = match (tag, bits)
+                {
+                    (1, 16) => SampleFormat::Int16,
+                    (1, 24) => SampleFormat::Int24,
+                    (3, 32) => SampleFormat::Float32,
+                    _ => return Err(invalid("unsupported sample format"))
+                };
+                if channels == 0 { return Err(invalid("no channels")); }
+                format = Some((sample_rate, channels, sf));
+            }
+            else if id == b"data"
+            {
+                let (sample_rate, channels, sf) = format.ok_or_else(|| invalid("data before format"))?;
+                let data = bytes[body..end].chunks_exact(sf.bytes()).map(|s| match sf
+                {
+                    SampleFormat::Int16 => i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0,
+                    SampleFormat::Int24 => (i32::from_le_bytes([0, s[0], s[1], s[2]]) >> 8) as f32 / 8388608.0,
+                    SampleFormat::Float32 => f32::from_le_bytes([s[0], s[1], s[2], s[3]])
+                }).collect();
+                return Ok(Wav::new(sample_rate, channels, sf, data));
+            }
+            
+            // chunks are padded to even sizes
+            at = end + (size & 1);
+        }
+        
+        return Err(invalid("no data chunk"));
+    }
+    pub fn write<W: Write>(&self, out: &mut W) -> io::Result<()>
+    {
+        let bytes = self.format.bytes();
+        let data_size = (self.data.len() * bytes) as u32;
+        let block = self.channels as u32 * bytes as u32;
+        
+        out.write_all(b"RIFF")?;
+        out.write_all(&(36 + data_size + (data_size & 1)).to_le_bytes())?;
+        out.write_all(b"WAVE")?;
+        
+        out.write_all(b"fmt ")?;
+        out.write_all(&16u32.to_le_bytes())?;
+        out.write_all(&self.format.tag().to_le_bytes())?;
+        out.write_all(&self.channels.to_le_bytes())?;
+        out.write_all(&self.sample_rate.to_le_bytes())?;
+        out.write_all(&(self.sample_rate * block).to_le_bytes())?;
+        out.write_all(&(block as u16).to_le_bytes())?;
+        out.write_all(&((bytes * 8) as u16).to_le_bytes())?;
+        
+        out.write_all(b"data")?;
+        out.write_all(&data_size.to_le_bytes())?;
+        let mut buffer = Vec::with_capacity(data_size as usize + 1);
+        for v in &self.data
+        {
+            let v = v.clamp(-1.0, 1.0);
+            match self.format
+            {
+                SampleFormat::Int16 =>
+                {
+                    let s = (v * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
+                    buffer.extend_from_slice(&s.to_le_bytes());
+                },
+                SampleFormat::Int24 =>
+                {
+                    let s = (v * 8388608.0).round().clamp(-8388608.0, 8388607.0) as i32;
+                    buffer.extend_from_slice(&s.to_le_bytes()[0..3]);
+                },
+                SampleFormat::Float32 => buffer.extend_from_slice(&v.to_le_bytes())
+            }
+        }
+        if data_size & 1 == 1 { buffer.push(0); }
+        return out.write_all(&buffer);
+    }
+}
+
+/// linear interpolation at `x` samples into a repeating `period`
+fn sample_periodic(period: &[f32], x: f64) -> f32
+{
+    let n = period.len();
+    let x = x % (n as f64);
+    let a = x.floor() as usize;
+    let f = (x - a as f64) as f32;
+    return period[a % n] + ((period[(a + 1) % n] - period[a % n]) * f);
+}
+
+/// one period in `data` stretched onto `len` points
+pub fn resample_period(data: &[f32], len: usize) -> Vec<f32>
+{
+    if data.is_empty() { return vec![0.0; len]; }
+    
+    let step = (data.len() as f64) / (len as f64);
+    return (0..len).map(|i| sample_periodic(data, i as f64 * step)).collect();
+}
+
+/// lag between `min` and `max` samples that best repeats the signal.
+/// the first peak within 90% of the strongest, so multiples of the period are skipped
+pub fn estimate_period(data: &[f32], min: usize, max: usize) -> Option<usize>
+{
+    let n = data.len();
+    let max = max.min(n.saturating_sub(1));
+    
+    let mean = data.iter().sum::<f32>() / (n as f32);
+    let centred: Vec<f32> = data.iter().map(|v| v - mean).collect();
+    let r = correlate(&centred, &centred);
+    // unbiased, fewer terms at longer lags
+    let at = |lag: usize| r[n - 1 + lag] / ((n - lag) as f32);
+    
+    // past the peak at lag 0
+    let start = (1..=max).find(|l| at(*l) <= 0.0)?.max(min);
+    let best = (start..=max).map(at).fold(0.0f32, f32::max);
+    if best <= 0.0 { return None; }
+    
+    let mut lag = (start..=max).find(|l| at(*l) >= best * 0.9)?;
+    while lag < max && at(lag + 1) > at(lag)
+    {
+        lag += 1;
+    }
+    return Some(lag);
+}
+
+/// `wave` as one period repeated at `frequency` for `len` samples
+pub fn loop_wave(wave: &[f32], sample_rate: u32, frequency: f32, len: usize) -> Vec<f32>
+{
+    if wave.is_empty() { return vec![0.0; len]; }
+    
+    let step = (frequency as f64) * (wave.len() as f64) / (sample_rate as f64);
+    return (0..len).map(|i| sample_periodic(wave, i as f64 * step)).collect();
+}
\ No newline at end of file
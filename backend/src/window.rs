@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+use num::{Float, traits::FloatConst};
+
+use crate::RepeatUntil;
+
+/// tapers applied to a plot before analysis to reduce leakage
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Window<T>
+{
+    #[default]
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+    /// shape parameter beta, larger trades resolution for lower side lobes
+    Kaiser(T)
+}
+
+impl<T> Display for Window<T>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return f.write_str(match self
+        {
+            Window::Rectangular => "Rectangular",
+            Window::Hann => "Hann",
+            Window::Hamming => "Hamming",
+            Window::Blackman => "Blackman",
+            Window::Kaiser(_) => "Kaiser"
+        });
+    }
+}
+
+/// modified bessel function of the first kind, order 0
+pub fn bessel_i0<T: Float>(x: T) -> T
+{
+    let q = (x * x) / T::from(4.0).unwrap();
+    let mut term = T::one();
+    let mut sum = T::one();
+    let mut k = T::one();
+    // terms are (x^2 / 4)^k / (k!)^2
+    while term > sum * T::epsilon()
+    {
+        term = term * q / (k * k);
+        sum = sum + term;
+        k = k + T::one();
+    }
+    return sum;
+}
+
+impl<T: Float + FloatConst> Window<T>
+{
+    /// periodic window of length `n`, so repeated plots stay continuous
+    pub fn coefficients(&self, n: usize) -> Vec<T>
+    {
+        let step = T::TAU() / T::from(n).unwrap();
+        let cos = |i: usize, k: f64| (step * T::from(i as f64 * k).unwrap()).cos();
+        let c = |v: f64| T::from(v).unwrap();
+        
+        return (0..n).map(|i| match self
+        {
+            Window::Rectangular => T::one(),
+            Window::Hann => c(0.5) - (c(0.5) * cos(i, 1.0)),
+            Window::Hamming => c(0.54) - (c(0.46) * cos(i, 1.0)),
+            Window::Blackman => c(0.42) - (c(0.5) * cos(i, 1.0)) + (c(0.08) * cos(i, 2.0)),
+            Window::Kaiser(beta) =>
+            {
+                // -1 to 1 across the plot
+                let x = (c(2.0) * T::from(i).unwrap() / T::from(n).unwrap()) - T::one();
+                bessel_i0(*beta * (T::one() - (x * x)).sqrt()) / bessel_i0(*beta)
+            }
+        }).collect();
+    }
+}
+
+/// mean of the coefficients, how much a windowed sinusoid's peak is reduced by
+pub fn coherent_gain<T: Float>(coefficients: &[T]) -> T
+{
+    if coefficients.len() == 0 { return T::one(); }
+    
+    let sum = coefficients.iter().fold(T::zero(), |a, c| a + *c);
+    return sum / T::from(coefficients.len()).unwrap();
+}
+
+/// how a plot is extended before analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Padding
+{
+    #[default]
+    None,
+    /// zeros to `n` times the length, interpolating the spectrum
+    Zero(usize),
+    /// the plot tiled `n` times
+    Repeat(usize)
+}
+
+impl Display for Padding
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return match self
+        {
+            Padding::None => f.write_str("No padding"),
+            Padding::Zero(n) => write!(f, "Zeros x{n}"),
+            Padding::Repeat(n) => write!(f, "Repeat x{n}")
+        };
+    }
+}
+
+impl Padding
+{
+    /// length of the transform for a plot of `n`
+    pub fn len(&self, n: usize) -> usize
+    {
+        return match self
+        {
+            Padding::None => n,
+            Padding::Zero(f) | Padding::Repeat(f) => n * (*f).max(1)
+        };
+    }
+    /// `plot` extended to `len(plot.len())`
+    pub fn apply<T: Float>(&self, plot: &[T]) -> Vec<T>
+    {
+        let len = self.len(plot.len());
+        return match self
+        {
+            Padding::None => plot.to_vec(),
+            Padding::Zero(_) => plot.iter().copied().chain(std::iter::repeat(T::zero()))
+                .take(len).collect(),
+            Padding::Repeat(_) => RepeatUntil::new(plot, len).copied().collect()
+        };
+    }
+    /// scale of a sinusoid's peak compared to no padding
+    pub fn gain<T: Float>(&self) -> T
+    {
+        return match self
+        {
+            Padding::Zero(f) => T::one() / T::from((*f).max(1)).unwrap(),
+            _ => T::one()
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,45 @@
+use backend::*;
+use num::Complex;
+
+#[test]
+fn minimum_phase_filter()
+{
+    // zeros inside the unit circle, already minimum phase
+    let size = 256;
+    let mut x = vec![0.0f64; size];
+    x[0] = 1.0;
+    x[1] = -0.5;
+    x[2] = 0.25;
+    
+    let plan = RealFftPlan::<f64>::new(size);
+    let expected = plan.forward(&x);
+    let mags: Vec<f64> = expected.iter().map(|c| c.norm()).collect();
+    let result = minimum_phase(&plan, &mags);
+    
+    assert_eq!(result.len(), expected.len());
+    for (r, e) in result.iter().zip(&expected)
+    {
+        assert!((r - e).norm() < 1e-9, "{r} != {e}");
+    }
+}
+
+#[test]
+fn minimum_phase_keeps_magnitude()
+{
+    let size = 128;
+    let plan = RealFftPlan::<f64>::new(size);
+    let mags: Vec<f64> = (0..plan.spectrum_len()).map(|k| 1.0 / (1.0 + k as f64)).collect();
+    let result = minimum_phase(&plan, &mags);
+    
+    for (r, m) in result.iter().zip(&mags)
+    {
+        assert!((r.norm() - m).abs() < 1e-9);
+    }
+    
+    // energy is packed at the start
+    let wave = plan.inverse_normalised(&result);
+    let zero: Vec<Complex<f64>> = mags.iter().map(|m| Complex::new(*m, 0.0)).collect();
+    let centred = plan.inverse_normalised(&zero);
+    let early = |w: &[f64]| w[..size / 8].iter().map(|v| v * v).sum::<f64>();
+    assert!(early(&wave) > early(&centred));
+}
\ No newline at end of file
@@ -0,0 +1,29 @@
+// each test uses only some of these
+#![allow(dead_code)]
+
+use num::{Complex, Float};
+use rand::{rngs::StdRng, Rng};
+
+pub fn random_complex<T: Float>(rng: &mut StdRng, size: usize) -> Vec<Complex<T>>
+{
+    return (0..size).map(|_| Complex::new(
+        T::from(rng.random_range(-1.0..1.0)).unwrap(),
+        T::from(rng.random_range(-1.0..1.0)).unwrap())).collect();
+}
+pub fn random_real<T: Float>(rng: &mut StdRng, size: usize) -> Vec<T>
+{
+    return (0..size).map(|_| T::from(rng.random_range(-1.0..1.0)).unwrap()).collect();
+}
+
+pub fn to_f64<T: Float>(x: &[Complex<T>]) -> Vec<Complex<f64>>
+{
+    return x.iter().map(|v| Complex::new(v.re.to_f64().unwrap(), v.im.to_f64().unwrap())).collect();
+}
+
+/// largest difference relative to the largest value
+pub fn error<T: Float>(a: &[Complex<T>], b: &[Complex<f64>]) -> f64
+{
+    let diff = to_f64(a).iter().zip(b).fold(0.0, |m: f64, (a, b)| m.max((a - b).norm()));
+    let scale = b.iter().fold(1.0, |m: f64, b| m.max(b.norm()));
+    return diff / scale;
+}
\ No newline at end of file
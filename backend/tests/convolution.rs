@@ -0,0 +1,138 @@
+use backend::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+mod common;
+use common::*;
+
+/// lengths either side of `DIRECT_MAX`, including empty and `b` longer than `a`
+const SIZES: [(usize, usize); 12] = [
+    (0, 0), (0, 5), (5, 0), (1, 1), (7, 3), (20, 30),
+    (DIRECT_MAX, DIRECT_MAX), (DIRECT_MAX + 1, DIRECT_MAX + 1),
+    (100, 40), (40, 100), (257, 65), (300, 7)
+];
+
+fn linear(a: &[f64], b: &[f64]) -> Vec<f64>
+{
+    if a.is_empty() || b.is_empty() { return Vec::new(); }
+    
+    return (0..(a.len() + b.len() - 1)).map(|k|
+    {
+        (0..b.len()).filter(|j| *j <= k && k - j < a.len()).map(|j| a[k - j] * b[j]).sum()
+    }).collect();
+}
+
+fn linear_correlation(a: &[f64], b: &[f64]) -> Vec<f64>
+{
+    if a.is_empty() || b.is_empty() { return Vec::new(); }
+    
+    let m = b.len() as isize;
+    return (0..(a.len() + b.len() - 1)).map(|k|
+    {
+        let lag = k as isize - (m - 1);
+        (0..m).filter(|j| (0..a.len() as isize).contains(&(j + lag)))
+            .map(|j| a[(j + lag) as usize] * b[j as usize]).sum()
+    }).collect();
+}
+
+fn circular(a: &[f64], b: &[f64]) -> Vec<f64>
+{
+    let n = a.len();
+    return (0..n).map(|i|
+    {
+        b.iter().enumerate().map(|(j, y)| a[(i + (n * b.len()) - j) % n] * y).sum()
+    }).collect();
+}
+
+fn circular_correlation(a: &[f64], b: &[f64]) -> Vec<f64>
+{
+    let n = a.len();
+    return (0..n).map(|lag|
+    {
+        b.iter().enumerate().map(|(j, y)| a[(j + lag) % n] * y).sum()
+    }).collect();
+}
+
+fn check(name: &str, (n, m): (usize, usize), result: &[f64], expected: &[f64])
+{
+    assert_eq!(result.len(), expected.len(), "{name} {n}, {m}");
+    for (k, (r, e)) in result.iter().zip(expected).enumerate()
+    {
+        assert!((r - e).abs() <= 1e-12 * (n + m) as f64, "{name} {n}, {m} at {k}: {r} != {e}");
+    }
+}
+
+#[test]
+fn linear_matches_direct()
+{
+    let mut rng = StdRng::seed_from_u64(1);
+    for (n, m) in SIZES
+    {
+        let a = random_real(&mut rng, n);
+        let b = random_real(&mut rng, m);
+        
+        check("convolve", (n, m), &convolve(&a, &b), &linear(&a, &b));
+        check("convolve_direct", (n, m), &convolve_direct(&a, &b), &linear(&a, &b));
+        check("correlate", (n, m), &correlate(&a, &b), &linear_correlation(&a, &b));
+    }
+}
+
+#[test]
+fn circular_matches_direct()
+{
+    let mut rng = StdRng::seed_from_u64(2);
+    for (n, m) in SIZES
+    {
+        let a = random_real(&mut rng, n);
+        let b = random_real(&mut rng, m);
+        
+        check("convolve_circular", (n, m), &convolve_circular(&a, &b), &circular(&a, &b));
+        check("correlate_circular", (n, m), &correlate_circular(&a, &b), &circular_correlation(&a, &b));
+    }
+}
+
+#[test]
+fn same_is_centred()
+{
+    let mut rng = StdRng::seed_from_u64(3);
+    let a = random_real(&mut rng, 100);
+    for m in [1, 5, 6, DIRECT_MAX + 3]
+    {
+        let kernel = random_real(&mut rng, m);
+        let full = linear(&a, &kernel);
+        let start = (m - 1) / 2;
+        check("convolve_same", (100, m), &convolve_same(&a, &kernel), &full[start..(start + 100)]);
+    }
+}
+#[test]
+fn plans_are_reused()
+{
+    let mut rng = StdRng::seed_from_u64(4);
+    let (n, m) = (200, 60);
+    let linear_plan = RealFftPlan::<f64>::new(convolution_len(n, m));
+    // any length long enough works
+    let odd_plan = RealFftPlan::<f64>::new(n + m + 5);
+    let circular_plan = RealFftPlan::<f64>::new(n);
+    for _ in 0..3
+    {
+        let a = random_real::<f64>(&mut rng, n);
+        let b = random_real::<f64>(&mut rng, m);
+        
+        check("convolve_with_plan", (n, m), &convolve_with_plan(&linear_plan, &a, &b), &linear(&a, &b));
+        check("convolve_with_plan odd", (n, m), &convolve_with_plan(&odd_plan, &a, &b), &linear(&a, &b));
+        check("correlate_with_plan", (n, m), &correlate_with_plan(&linear_plan, &a, &b), &linear_correlation(&a, &b));
+        let start = (m - 1) / 2;
+        check("convolve_same_with_plan", (n, m), &convolve_same_with_plan(&linear_plan, &a, &b),
+            &linear(&a, &b)[start..(start + n)]);
+        check("convolve_circular_with_plan", (n, m), &convolve_circular_with_plan(&circular_plan, &a, &b), &circular(&a, &b));
+        check("correlate_circular_with_plan", (n, m), &correlate_circular_with_plan(&circular_plan, &a, &b),
+            &circular_correlation(&a, &b));
+    }
+}
+
+#[test]
+#[should_panic]
+fn short_plan_panics()
+{
+    let a = vec![1.0; 100];
+    convolve_with_plan(&RealFftPlan::<f64>::new(128), &a, &a);
+}
\ No newline at end of file
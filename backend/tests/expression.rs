@@ -0,0 +1,51 @@
+use backend::*;
+
+fn eval(src: &str, vars: &[(&str, f64)]) -> f64
+{
+    let names: Vec<&str> = vars.iter().map(|v| v.0).collect();
+    let values: Vec<f64> = vars.iter().map(|v| v.1).collect();
+    return parse(src, &names).unwrap().eval(&values);
+}
+
+fn close(a: f64, b: f64)
+{
+    assert!((a - b).abs() < 1e-12, "{a} != {b}");
+}
+
+#[test]
+fn precedence()
+{
+    close(eval("1 + 2 * 3", &[]), 7.0);
+    close(eval("(1 + 2) * 3", &[]), 9.0);
+    close(eval("8 / 4 / 2", &[]), 1.0);
+    close(eval("2 ^ 3 ^ 2", &[]), 512.0);
+    close(eval("-2 ^ 2", &[]), -4.0);
+    close(eval("2 ^ -1", &[]), 0.5);
+    close(eval("1 - -1", &[]), 2.0);
+    close(eval("1.5e2 + .5", &[]), 150.5);
+}
+
+#[test]
+fn functions()
+{
+    let t = 0.125;
+    close(eval("sin(2*pi*t) + 0.3*sin(6*pi*t)", &[("t", t)]),
+        (std::f64::consts::TAU * t).sin() + 0.3 * (6.0 * std::f64::consts::PI * t).sin());
+    close(eval("exp(-((l-550)/20)^2)", &[("l", 570.0)]), (-1.0f64).exp());
+    close(eval("max(1, min(4, 3)) + atan2(0, 1) + abs(-2)", &[]), 5.0);
+    close(eval("ln(e) + log(100) + sqrt(tau / pi * 2)", &[]), 5.0);
+}
+
+#[test]
+fn errors()
+{
+    let error = |src: &str| parse(src, &["t"]).unwrap_err();
+    
+    assert_eq!(error("1 +").position, 3);
+    assert_eq!(error("sin(t").message, "expected ')'");
+    assert_eq!(error("2 * x").message, "unknown name 'x'");
+    assert_eq!(error("1 $ 2").position, 2);
+    assert_eq!(error("(1 + 2))").position, 7);
+    assert_eq!(error("min(1)").position, 0);
+    assert_eq!(error("sin 1").message, "expected '(' after function");
+}
\ No newline at end of file
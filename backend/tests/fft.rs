@@ -0,0 +1,195 @@
+use backend::*;
+use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+mod common;
+use common::*;
+
+/// powers of 2, smooth lengths for mixed radix and primes for bluestein
+const SIZES: [usize; 16] = [1, 2, 3, 4, 5, 7, 8, 12, 17, 30, 64, 100, 127, 250, 256, 257];
+
+/// O(n^2) reference in f64, `invert` as for `WCache`
+fn naive_dft<T: Float>(x: &[Complex<T>], invert: bool) -> Vec<Complex<f64>>
+{
+    let n = x.len();
+    let sign = if invert { -1.0 } else { 1.0 };
+    return (0..n).map(|k|
+    {
+        x.iter().enumerate().fold(Complex::new(0.0, 0.0), |a, (j, v)|
+        {
+            // reduced first so large sizes keep their accuracy
+            let angle = sign * std::f64::consts::TAU * ((j * k) % n) as f64 / n as f64;
+            let v = Complex::new(v.re.to_f64().unwrap(), v.im.to_f64().unwrap());
+            a + (v * Complex::cis(angle))
+        })
+    }).collect();
+}
+
+fn forward<T: Float + ConstOne + ConstZero + FloatConst + 'static>(x: &[Complex<T>]) -> Vec<Complex<T>>
+{
+    let mut y = x.to_vec();
+    FftPlan::<T>::new(x.len()).forward(&mut y);
+    return y;
+}
+/// not normalised
+fn inverse<T: Float + ConstOne + ConstZero + FloatConst + 'static>(x: &[Complex<T>]) -> Vec<Complex<T>>
+{
+    let mut y = x.to_vec();
+    FftPlan::<T>::new(x.len()).inverse(&mut y);
+    return y;
+}
+
+/// `dft_with_scratch` on a cache shared by every size
+fn cached<T: Float + ConstOne + ConstZero + FloatConst>(wn: &mut WCache<T>, x: &[Complex<T>]) -> Vec<Complex<T>>
+{
+    wn.ensure_len(x.len());
+    let mut y = x.to_vec();
+    let mut scratch = vec![Complex::<T>::ZERO; scratch_len(x.len())];
+    dft_with_scratch(wn, &mut y, &mut scratch);
+    return y;
+}
+
+fn check_dft<T: Float + ConstOne + ConstZero + FloatConst + 'static>(tolerance: f64)
+{
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut wn_forward = WCache::<T>::new(true);
+    let mut wn_inverse = WCache::<T>::new(false);
+    for size in SIZES
+    {
+        let x = random_complex::<T>(&mut rng, size);
+        let expected_forward = naive_dft(&x, true);
+        let expected_inverse = naive_dft(&x, false);
+        
+        let e = error(&forward(&x), &expected_forward);
+        assert!(e <= tolerance, "forward size {size}: error {e}");
+        let e = error(&inverse(&x), &expected_inverse);
+        assert!(e <= tolerance, "inverse size {size}: error {e}");
+        
+        let e = error(&cached(&mut wn_forward, &x), &expected_forward);
+        assert!(e <= tolerance, "cached forward size {size}: error {e}");
+        let e = error(&cached(&mut wn_inverse, &x), &expected_inverse);
+        assert!(e <= tolerance, "cached inverse size {size}: error {e}");
+    }
+}
+
+#[test]
+fn dft_matches_naive_f64()
+{
+    check_dft::<f64>(1e-12);
+}
+
+#[test]
+fn dft_matches_naive_f32()
+{
+    check_dft::<f32>(1e-4);
+}
+
+fn check_analysis<T: Float + ConstOne + ConstZero + FloatConst + 'static>(tolerance: f64)
+{
+    let mut rng = StdRng::seed_from_u64(2);
+    for size in SIZES
+    {
+        let plot = random_real::<T>(&mut rng, size);
+        let complex: Vec<Complex<T>> = plot.iter().map(|v| Complex::new(*v, T::zero())).collect();
+        let expected = naive_dft(&complex, true);
+        
+        let plan = RealFftPlan::<T>::new(size);
+        let analysis = dft_analysis(&plan, &plot);
+        assert_eq!(analysis.len(), (size / 2) + 1);
+        let e = error(&analysis, &expected[..analysis.len()]);
+        assert!(e <= tolerance, "analysis size {size}: error {e}");
+        
+        let back: Vec<Complex<T>> = form_plot(&plan, &analysis).iter()
+            .map(|v| Complex::new(*v, T::zero())).collect();
+        let plot: Vec<Complex<f64>> = plot.iter().map(|v| Complex::new(v.to_f64().unwrap(), 0.0)).collect();
+        let e = error(&back, &plot);
+        assert!(e <= tolerance, "plot size {size}: error {e}");
+    }
+}
+
+#[test]
+fn analysis_matches_naive_f64()
+{
+    check_analysis::<f64>(1e-12);
+}
+
+#[test]
+fn analysis_matches_naive_f32()
+{
+    check_analysis::<f32>(1e-4);
+}
+
+#[test]
+fn parseval()
+{
+    let mut rng = StdRng::seed_from_u64(3);
+    for size in SIZES
+    {
+        let x = random_complex::<f64>(&mut rng, size);
+        let y = forward(&x);
+        
+        let time = x.iter().fold(0.0, |a, c| a + c.norm_sqr());
+        let freq = y.iter().fold(0.0, |a, c| a + c.norm_sqr()) / size as f64;
+        assert!((time - freq).abs() <= time * 1e-12, "size {size}: {time} and {freq}");
+    }
+}
+
+#[test]
+fn round_trip()
+{
+    let mut rng = StdRng::seed_from_u64(4);
+    for size in SIZES
+    {
+        let x = random_complex::<f64>(&mut rng, size);
+        let back: Vec<Complex<f64>> = inverse(&forward(&x)).iter().map(|c| c / size as f64).collect();
+        let e = error(&back, &x);
+        assert!(e <= 1e-12, "size {size}: error {e}");
+    }
+}
+
+/// seeded random lengths and data, checks that hold for any input
+#[test]
+fn seeded_random_inputs()
+{
+    let mut rng = StdRng::seed_from_u64(5);
+    for _ in 0..100
+    {
+        let size = rng.random_range(1..=300);
+        let x = random_complex::<f64>(&mut rng, size);
+        let z = random_complex::<f64>(&mut rng, size);
+        let a = rng.random_range(-2.0..2.0);
+        let fx = forward(&x);
+        let fz = forward(&z);
+        
+        // matches the reference
+        let e = error(&fx, &naive_dft(&x, true));
+        assert!(e <= 1e-12, "size {size}: error {e}");
+        
+        // linear
+        let sum: Vec<Complex<f64>> = x.iter().zip(&z).map(|(x, z)| (x * a) + z).collect();
+        let expected: Vec<Complex<f64>> = fx.iter().zip(&fz).map(|(x, z)| (x * a) + z).collect();
+        let e = error(&forward(&sum), &expected);
+        assert!(e <= 1e-12, "linearity size {size}: error {e}");
+        
+        // a circular shift of one multiplies bin k by e^(-2 pi i k / n)
+        let mut shifted = x.clone();
+        shifted.rotate_right(1);
+        let expected: Vec<Complex<f64>> = fx.iter().enumerate()
+            .map(|(k, c)| c * Complex::cis(-std::f64::consts::TAU * k as f64 / size as f64)).collect();
+        let e = error(&forward(&shifted), &expected);
+        assert!(e <= 1e-12, "shift size {size}: error {e}");
+        
+        // real input has a conjugate symmetric spectrum
+        let real: Vec<Complex<f64>> = x.iter().map(|c| Complex::new(c.re, 0.0)).collect();
+        let fr = forward(&real);
+        for k in 1..size
+        {
+            assert!((fr[k] - fr[size - k].conj()).norm() <= 1e-12 * size as f64, "symmetry size {size}, bin {k}");
+        }
+        
+        // round trip
+        let back: Vec<Complex<f64>> = inverse(&fx).iter().map(|c| c / size as f64).collect();
+        let e = error(&back, &x);
+        assert!(e <= 1e-12, "round trip size {size}: error {e}");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,124 @@
+use backend::*;
+use num::Complex;
+
+const HALF_POWER: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+fn filter(kind: FilterKind, order: u32) -> SpectralFilter<f64>
+{
+    return SpectralFilter::new(kind, 20.0, 8.0, order);
+}
+
+/// lower and upper half power frequencies of a band
+fn band_edges(f: &SpectralFilter<f64>) -> (f64, f64)
+{
+    let root = ((f.width * f.width) + (4.0 * f.cutoff * f.cutoff)).sqrt();
+    return ((root - f.width) * 0.5, (root + f.width) * 0.5);
+}
+
+#[test]
+fn half_power_at_edges()
+{
+    for order in [1, 2, 4, 8]
+    {
+        for kind in FilterKind::ALL
+        {
+            let f = filter(kind, order);
+            let edges = match kind.is_band()
+            {
+                true =>
+                {
+                    let (lower, upper) = band_edges(&f);
+                    assert!((upper - lower - f.width).abs() < 1e-12);
+                    vec![lower, upper]
+                },
+                false => vec![f.cutoff]
+            };
+            
+            for e in edges
+            {
+                let g = f.gain(e);
+                assert!((g - HALF_POWER).abs() < 1e-12, "{kind} order {order} at {e}: {g}");
+            }
+        }
+    }
+}
+
+#[test]
+fn unity_in_passband()
+{
+    for order in [2, 4]
+    {
+        let low = filter(FilterKind::LowPass, order);
+        assert_eq!(low.gain(0.0), 1.0);
+        assert!((low.gain(1.0) - 1.0).abs() < 1e-4);
+        
+        let high = filter(FilterKind::HighPass, order);
+        assert!((high.gain(400.0) - 1.0).abs() < 1e-4);
+        
+        let band = filter(FilterKind::BandPass, order);
+        assert_eq!(band.gain(band.cutoff), 1.0);
+        
+        let notch = filter(FilterKind::Notch, order);
+        assert_eq!(notch.gain(0.0), 1.0);
+        assert!((notch.gain(400.0) - 1.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn stop_band()
+{
+    assert_eq!(filter(FilterKind::HighPass, 2).gain(0.0), 0.0);
+    assert_eq!(filter(FilterKind::BandPass, 2).gain(0.0), 0.0);
+    let notch = filter(FilterKind::Notch, 2);
+    assert_eq!(notch.gain(notch.cutoff), 0.0);
+    assert!(filter(FilterKind::LowPass, 4).gain(400.0) < 1e-5);
+}
+
+#[test]
+fn apply_endpoints()
+{
+    // 128 point real transform, bins 0 to the nyquist bin 64
+    let size = 65;
+    for kind in FilterKind::ALL
+    {
+        let f = filter(kind, 3);
+        let mut spectrum = vec![Complex::new(1.0, -2.0); size];
+        f.apply(&mut spectrum, 1.0);
+        
+        let dc = spectrum[0];
+        let nyquist = spectrum[size - 1];
+        assert_eq!(dc, Complex::new(1.0, -2.0) * f.gain(0.0), "{kind} dc");
+        assert_eq!(nyquist, Complex::new(1.0, -2.0) * f.gain(64.0), "{kind} nyquist");
+    }
+    
+    let mut spectrum = vec![Complex::new(1.0, 0.0); size];
+    filter(FilterKind::HighPass, 3).apply(&mut spectrum, 1.0);
+    assert_eq!(spectrum[0].norm(), 0.0);
+    assert!((spectrum[size - 1].norm() - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn apply_fractional_step()
+{
+    // zero padding by 4 puts four bins between harmonics
+    let step = 0.25;
+    for kind in FilterKind::ALL
+    {
+        let f = filter(kind, 2);
+        let mut spectrum = vec![Complex::new(1.0, 0.0); 200];
+        f.apply(&mut spectrum, step);
+        
+        for (k, c) in spectrum.iter().enumerate()
+        {
+            assert_eq!(c.re, f.gain(k as f64 * step), "{kind} bin {k}");
+        }
+        // harmonic 20 is at bin 80
+        let expected = match kind
+        {
+            FilterKind::Notch => 0.0,
+            FilterKind::BandPass => 1.0,
+            _ => HALF_POWER
+        };
+        assert!((spectrum[80].re - expected).abs() < 1e-12, "{kind}: {}", spectrum[80].re);
+    }
+}
\ No newline at end of file
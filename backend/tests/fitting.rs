@@ -0,0 +1,94 @@
+use std::io::Cursor;
+
+use backend::*;
+use zene_structs::Vector2;
+
+#[test]
+fn nelder_mead_rosenbrock()
+{
+    let f = |p: &[f64]| (1.0 - p[0]).powi(2) + (100.0 * (p[1] - (p[0] * p[0])).powi(2));
+    let (p, v) = nelder_mead(f, &[-1.2, 1.0], &[0.5, 0.5], 5000, 1e-14, |_, _| true);
+    
+    assert!(v < 1e-8, "{v}");
+    assert!((p[0] - 1.0).abs() < 1e-3 && (p[1] - 1.0).abs() < 1e-3, "{p:?}");
+}
+
+#[test]
+fn nelder_mead_stops_on_progress()
+{
+    let mut calls = 0;
+    nelder_mead(|p: &[f64]| p[0] * p[0], &[5.0], &[1.0], 1000, 0.0, |i, _|
+    {
+        calls = i;
+        return i < 3;
+    });
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn invert()
+{
+    let a = vec![vec![4.0, 7.0, 2.0], vec![3.0, 6.0, 1.0], vec![2.0, 5.0, 3.0]];
+    let mut inv = a.clone();
+    assert!(invert_matrix(&mut inv));
+    
+    for (i, row) in a.iter().enumerate()
+    {
+        for j in 0..3
+        {
+            let v: f64 = row.iter().zip(&inv).map(|(x, r)| x * r[j]).sum();
+            let e = if i == j { 1.0 } else { 0.0 };
+            assert!((v - e).abs() < 1e-12, "{i} {j}: {v}");
+        }
+    }
+    
+    let mut singular = vec![vec![1.0, 2.0], vec![2.0, 4.0]];
+    assert!(!invert_matrix(&mut singular));
+    let mut nan = vec![vec![f64::NAN, 0.0], vec![0.0, 1.0]];
+    assert!(!invert_matrix(&mut nan));
+}
+
+#[test]
+fn csv_skips_headers_and_non_finite()
+{
+    let csv = "position,total\n0,1\n1,nan\n2, 3\ninf,4\n3,5,extra\n";
+    let rows: Vec<(f64, f64)> = read_csv_columns(Cursor::new(csv), 0, 1).unwrap();
+    assert_eq!(rows, vec![(0.0, 1.0), (2.0, 3.0), (3.0, 5.0)]);
+}
+
+#[test]
+fn recovers_double_slit()
+{
+    // lengths in nm
+    let waves = [Wave::new(500.0, 1.0)];
+    let (width, separation, centre) = (2000.0, 8000.0, 30000.0);
+    let distance = 1e6;
+    let (start, end) = (-5e5, 5e5);
+    let count = 400;
+    
+    let slits: Vec<Slit<'_, f64>> = [-0.5, 0.5].iter().map(|j|
+    {
+        let x = centre + (j * separation);
+        return Slit::new(width, Vector2::new(x, 0.0), Vector2::new(0.0, 1.0), &waves);
+    }).collect();
+    let env = EMEnv::new(Vector2::new(start, distance), Vector2::new(end, distance));
+    let mut intensity = vec![0.0; count];
+    env.generate_intensity(&slits, &[500.0], &mut intensity);
+    
+    let step = (end - start) / ((count - 1) as f64);
+    let measured: Vec<(f64, f64)> = intensity.iter().enumerate()
+        .map(|(i, v)| (start + (i as f64 * step), *v)).collect();
+    
+    let fit = fit_slits(&measured, distance, &waves, 3).unwrap();
+    assert_eq!(fit.count, 2);
+    assert!((fit.width - width).abs() < width * 0.02, "{}", fit.width);
+    assert!((fit.separation - separation).abs() < separation * 0.01, "{}", fit.separation);
+    assert!((fit.offset - centre).abs() < step, "{}", fit.offset);
+}
+#[test]
+fn dark_spectrum_has_no_fit()
+{
+    let waves = [Wave::new(500.0, 0.0), Wave::new(600.0, 0.0)];
+    let measured: Vec<(f64, f64)> = (0..100).map(|i| (i as f64 * 100.0, (i as f64 * 0.3).cos().powi(2))).collect();
+    assert!(fit_slits(&measured, 1e6, &waves, 3).is_none());
+}
\ No newline at end of file
@@ -0,0 +1,133 @@
+use backend::*;
+use num::Complex;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+mod common;
+use common::*;
+
+fn naive_2d(x: &[Complex<f64>], width: usize, height: usize) -> Vec<Complex<f64>>
+{
+    let tau = -std::f64::consts::TAU;
+    return (0..(width * height)).map(|i|
+    {
+        let (u, v) = (i % width, i / width);
+        x.iter().enumerate().fold(Complex::new(0.0, 0.0), |a, (j, c)|
+        {
+            let (x, y) = (j % width, j / width);
+            let t = (((u * x) % width) as f64 / width as f64) + (((v * y) % height) as f64 / height as f64);
+            a + (c * Complex::cis(tau * t))
+        })
+    }).collect();
+}
+
+#[test]
+fn plan_2d_matches_naive()
+{
+    let mut rng = StdRng::seed_from_u64(1);
+    for (width, height) in [(1, 1), (4, 3), (8, 8), (6, 10), (16, 5), (1, 7)]
+    {
+        let data = random_complex(&mut rng, width * height);
+        let plan = FftPlan2D::<f64>::new(width, height);
+        
+        let mut y = data.clone();
+        plan.forward(&mut y);
+        let e = error(&y, &naive_2d(&data, width, height));
+        assert!(e < 1e-12, "{width} by {height}: error {e}");
+        
+        plan.inverse_normalised(&mut y);
+        let e = error(&y, &data);
+        assert!(e < 1e-12, "{width} by {height} round trip: error {e}");
+    }
+}
+
+#[test]
+fn propagate_1d_plane_waves()
+{
+    let (n, dx, lambda, z) = (64, 100.0, 500.0, 3000.0);
+    let plan = FftPlan::<f64>::new(n);
+    let rl2 = (1.0 / lambda) * (1.0 / lambda);
+    
+    // propagating and evanescent bins
+    for k in [0, 1, 5, 13, 20, 31, 50]
+    {
+        let f = spatial_frequency(k, n, dx);
+        let wave: Vec<Complex<f64>> = (0..n).map(|i| Complex::cis(std::f64::consts::TAU * (k * i) as f64 / n as f64)).collect();
+        let mut field = wave.clone();
+        propagate_1d(&plan, &mut field, dx, lambda, z);
+        
+        let t = transfer(f * f, rl2, z);
+        let expected: Vec<Complex<f64>> = wave.iter().map(|w| w * t).collect();
+        let e = error(&field, &expected);
+        assert!(e < 1e-12, "bin {k}: error {e}");
+        assert_eq!(t.norm() < 1.0, f * f > rl2, "bin {k}");
+    }
+}
+
+#[test]
+fn propagate_2d_matches_1d()
+{
+    let mut rng = StdRng::seed_from_u64(2);
+    let (width, height, dx, dy, lambda, z) = (32, 8, 120.0, 90.0, 600.0, 2500.0);
+    // every row the same, so only fy = 0 carries light
+    let row = random_complex(&mut rng, width);
+    let mut field: Vec<Complex<f64>> = (0..height).flat_map(|_| row.iter().copied()).collect();
+    propagate_2d(&FftPlan2D::new(width, height), &mut field, dx, dy, lambda, z);
+    
+    let mut expected = row.clone();
+    propagate_1d(&FftPlan::new(width), &mut expected, dx, lambda, z);
+    for r in field.chunks_exact(width)
+    {
+        let e = error(r, &expected);
+        assert!(e < 1e-12, "error {e}");
+    }
+}
+
+#[test]
+fn slits_add_their_value()
+{
+    let mut rng = StdRng::seed_from_u64(3);
+    let dx: f64 = 50.0;
+    for _ in 0..20
+    {
+        let mut field = vec![Complex::new(0.0, 0.0); 256];
+        let left: f64 = rng.random_range(-4000.0..4000.0);
+        let width = rng.random_range(1.0..2000.0);
+        let value = Complex::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0));
+        add_slit(&mut field, dx, left, left + width, value);
+        
+        let total = field.iter().fold(Complex::new(0.0, 0.0), |a, c| a + c);
+        assert!((total - value).norm() < 1e-12, "{left} + {width}: {total} != {value}");
+        // the weights are the fraction of the slit over each sample
+        let ends = [(left / dx) + 0.5, ((left + width) / dx) + 0.5].map(|u| u.floor());
+        assert!(field.iter().filter(|c| c.norm() > 0.0).count() <= (ends[1] - ends[0]) as usize + 1);
+    }
+}
+
+#[test]
+fn far_field_of_a_slit_is_a_sinc()
+{
+    let (n, lambda, width) = (1 << 12, 500.0, 1560.0);
+    let dx = lambda / 8.0;
+    let plan = FftPlan::<f64>::new(n);
+    let value = Complex::from_polar(0.7, 0.3);
+    
+    // off centre, so the spectrum turns with the angle
+    let centre = 300.0;
+    let mut field = vec![Complex::new(0.0, 0.0); n];
+    add_slit(&mut field, dx, centre - (width * 0.5), centre + (width * 0.5), value);
+    // far enough that the Fresnel phase is negligible
+    far_field(&plan, &mut field, dx, lambda, 1e12);
+    
+    assert!((field[0] - value).norm() < 1e-6);
+    for i in 0..=100
+    {
+        let sin = (i as f64 / 50.0) - 1.0;
+        let f = sin / lambda;
+        let beta = std::f64::consts::PI * width * f;
+        let sinc = if beta == 0.0 { 1.0 } else { beta.sin() / beta };
+        let expected = value * sinc * Complex::cis(-std::f64::consts::TAU * f * centre);
+        
+        let c = sample_far_field(&field, dx, f);
+        assert!((c - expected).norm() < 1e-2 * value.norm(), "sin {sin}: {c} != {expected}");
+    }
+}
\ No newline at end of file
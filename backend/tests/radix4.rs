@@ -0,0 +1,95 @@
+use backend::*;
+use num::{traits::{ConstOne, ConstZero, FloatConst}, Complex, Float};
+use rand::{rngs::StdRng, SeedableRng};
+
+mod common;
+use common::*;
+
+/// sizes above this are checked against an f64 transform instead of the O(n^2) sum
+const NAIVE_MAX: usize = 1 << 12;
+
+/// in f64, `invert` as for `WCache`
+fn reference<T: Float>(x: &[Complex<T>], invert: bool) -> Vec<Complex<f64>>
+{
+    let n = x.len();
+    let x = to_f64(x);
+    if n > NAIVE_MAX
+    {
+        let power = n.trailing_zeros() as usize;
+        let mut wn = WCache::<f64>::new(invert);
+        wn.ensure_max_power(power);
+        let mut y = x;
+        let mut scratch = vec![Complex::new(0.0, 0.0); n];
+        fft_iterative_scratch(&wn, &mut y, power, &mut scratch);
+        return y;
+    }
+    
+    let sign = if invert { -1.0 } else { 1.0 };
+    let roots: Vec<Complex<f64>> = (0..n)
+        .map(|i| Complex::cis(sign * std::f64::consts::TAU * i as f64 / n as f64)).collect();
+    return (0..n).map(|k|
+    {
+        x.iter().enumerate().fold(Complex::new(0.0, 0.0), |a, (j, v)| a + (v * roots[(j * k) % n]))
+    }).collect();
+}
+
+fn compare<T>(rng: &mut StdRng, power: usize, invert: bool, tolerance: f64)
+    where T: Float + ConstOne + ConstZero + FloatConst + 'static
+{
+    let size = 1 << power;
+    let data = random_complex::<T>(rng, size);
+    let expected = reference(&data, invert);
+    
+    let plan = Radix4::<T>::new(size, invert);
+    let mut scratch = vec![Complex::<T>::ZERO; size];
+    
+    let mut simd = data.clone();
+    fft_radix4(&plan, &mut simd, &mut scratch);
+    let e = error(&simd, &expected);
+    assert!(e <= tolerance, "size {size}, invert {invert}: error {e}");
+    
+    let mut scalar = data.clone();
+    fft_radix4_scalar(&plan, &mut scalar, &mut scratch);
+    let e = error(&scalar, &expected);
+    assert!(e <= tolerance, "scalar size {size}, invert {invert}: error {e}");
+}
+
+#[test]
+fn radix4_matches_reference_f64()
+{
+    let mut rng = StdRng::seed_from_u64(1);
+    for power in 0..=16
+    {
+        compare::<f64>(&mut rng, power, true, 1e-12);
+        compare::<f64>(&mut rng, power, false, 1e-12);
+    }
+}
+
+#[test]
+fn radix4_matches_reference_f32()
+{
+    let mut rng = StdRng::seed_from_u64(2);
+    for power in 0..=16
+    {
+        compare::<f32>(&mut rng, power, true, 1e-5);
+        compare::<f32>(&mut rng, power, false, 1e-5);
+    }
+}
+
+#[test]
+fn radix4_plan_round_trip()
+{
+    let mut rng = StdRng::seed_from_u64(3);
+    for power in [1, 2, 5, 10, 13]
+    {
+        let size = 1 << power;
+        let data = random_complex::<f32>(&mut rng, size);
+        let plan = FftPlan::<f32>::new(size);
+        
+        let mut y = data.clone();
+        plan.forward(&mut y);
+        plan.inverse_normalised(&mut y);
+        let e = error(&y, &to_f64(&data));
+        assert!(e <= 1e-5, "size {size}: error {e}");
+    }
+}
\ No newline at end of file
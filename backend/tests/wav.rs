@@ -0,0 +1,94 @@
+use std::f32::consts::TAU;
+use std::io::Cursor;
+
+use backend::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+mod common;
+use common::*;
+
+fn round_trip(wav: &Wav) -> Wav
+{
+    let mut bytes = Vec::new();
+    wav.write(&mut bytes).unwrap();
+    // riff size covers the rest of the file
+    assert_eq!(u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize, bytes.len() - 8);
+    return Wav::read(Cursor::new(bytes)).unwrap();
+}
+
+#[test]
+fn formats()
+{
+    let cases = [(SampleFormat::Int16, 1.0 / 32768.0), (SampleFormat::Int24, 1.0 / 8388608.0),
+        (SampleFormat::Float32, 0.0)];
+    for (format, tolerance) in cases
+    {
+        // odd lengths need padding with 24 bit samples
+        for (channels, len) in [(1, 101), (2, 64)]
+        {
+            let wav = Wav::new(44100, channels, format, random_real(&mut StdRng::seed_from_u64(len as u64), len * channels as usize));
+            let read = round_trip(&wav);
+            
+            assert_eq!(read.sample_rate, 44100);
+            assert_eq!(read.channels, channels);
+            assert_eq!(read.format, format);
+            assert_eq!(read.len(), len);
+            for (a, b) in wav.data.iter().zip(&read.data)
+            {
+                assert!((a - b).abs() <= tolerance, "{format:?}: {a} != {b}");
+            }
+        }
+    }
+}
+
+#[test]
+fn full_scale()
+{
+    let wav = Wav::new(8000, 1, SampleFormat::Int16, vec![-1.0, 1.0, 0.0, 2.0]);
+    let read = round_trip(&wav);
+    assert_eq!(read.data[0], -1.0);
+    assert_eq!(read.data[1], 32767.0 / 32768.0);
+    assert_eq!(read.data[2], 0.0);
+    // clipped
+    assert_eq!(read.data[3], 32767.0 / 32768.0);
+}
+
+#[test]
+fn skips_chunks()
+{
+    let wav = Wav::new(22050, 1, SampleFormat::Float32, random_real(&mut StdRng::seed_from_u64(3), 10));
+    let mut bytes = Vec::new();
+    wav.write(&mut bytes).unwrap();
+    
+    // odd sized chunk before the format
+    let mut extra = b"LIST".to_vec();
+    extra.extend_from_slice(&3u32.to_le_bytes());
+    extra.extend_from_slice(&[1, 2, 3, 0]);
+    bytes.splice(12..12, extra);
+    
+    assert_eq!(Wav::read(Cursor::new(bytes)).unwrap(), wav);
+    assert!(Wav::read(Cursor::new(b"RIFF\0\0\0\0WAVE".to_vec())).is_err());
+    assert!(Wav::read(Cursor::new(b"not a wav".to_vec())).is_err());
+}
+
+#[test]
+fn mono()
+{
+    let wav = Wav::new(8000, 2, SampleFormat::Float32, vec![1.0, 0.0, -0.5, 0.5]);
+    assert_eq!(wav.mono(), vec![0.5, 0.0]);
+}
+
+#[test]
+fn periods()
+{
+    let data: Vec<f32> = (0..2000).map(|i| (TAU * i as f32 / 50.0).sin() + 0.3 * (TAU * i as f32 / 25.0).sin()).collect();
+    assert_eq!(estimate_period(&data, 2, 400), Some(50));
+    assert_eq!(estimate_period(&[0.0; 100], 2, 50), None);
+    
+    let ramp = [0.0, 1.0, 2.0, 3.0];
+    assert_eq!(resample_period(&ramp, 8), vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 1.5]);
+    
+    // 4 periods of the ramp
+    let looped = loop_wave(&ramp, 16, 4.0, 16);
+    assert_eq!(looped, [ramp; 4].concat());
+}
\ No newline at end of file
@@ -37,10 +37,11 @@ fn it_bench(b: &mut Bencher, data: &(&WCache<f32>, &[Complex<f32>], usize))
     {
         let mut copy = vec![Complex::<f32>::ZERO; data.1.len()];
         copy.copy_from_slice(data.1);
+        let mut scratch = vec![Complex::<f32>::ZERO; data.1.len()];
         let start = Instant::now();
         for _ in 0..iters
         {
-            fft_iterative_v2(data.0, copy.as_mut_slice(), data.2);
+            fft_iterative_scratch(data.0, copy.as_mut_slice(), data.2, scratch.as_mut_slice());
         }
         return start.elapsed();
     } )
@@ -103,6 +104,152 @@ fn bench_all(group: &mut BenchmarkGroup<'_, WallTime>, power: usize)
         it2_bench);
 }
 
+fn dft_bench(b: &mut Bencher, data: &(&WCache<f32>, &[Complex<f32>]))
+{
+    b.iter_custom(|iters|
+    {
+        let mut copy = vec![Complex::<f32>::ZERO; data.1.len()];
+        copy.copy_from_slice(data.1);
+        let mut scratch = vec![Complex::<f32>::ZERO; scratch_len(data.1.len())];
+        let start = Instant::now();
+        for _ in 0..iters
+        {
+            dft_with_scratch(data.0, copy.as_mut_slice(), scratch.as_mut_slice());
+        }
+        return start.elapsed();
+    } )
+}
+
+fn plan_bench(b: &mut Bencher, data: &(&FftPlan<f32>, &[Complex<f32>]))
+{
+    b.iter_custom(|iters|
+    {
+        let mut copy = vec![Complex::<f32>::ZERO; data.1.len()];
+        copy.copy_from_slice(data.1);
+        let start = Instant::now();
+        for _ in 0..iters
+        {
+            data.0.forward(copy.as_mut_slice());
+        }
+        return start.elapsed();
+    } )
+}
+
+fn bench_len(group: &mut BenchmarkGroup<'_, WallTime>, size: usize)
+{
+    let data = gen_data(size);
+    let mut wn = WCache::<f32>::new(false);
+    wn.ensure_len(size);
+    
+    group.bench_with_input(
+        BenchmarkId::new("Any_Length", size),
+        &(&wn, data.as_ref()),
+        dft_bench);
+    
+    let plan = FftPlan::<f32>::new(size);
+    group.bench_with_input(
+        BenchmarkId::new("Plan", size),
+        &(&plan, data.as_ref()),
+        plan_bench);
+}
+
+fn radix4_bench(b: &mut Bencher, data: &(&Radix4<f32>, &[Complex<f32>]))
+{
+    b.iter_custom(|iters|
+    {
+        let mut copy = vec![Complex::<f32>::ZERO; data.1.len()];
+        copy.copy_from_slice(data.1);
+        let mut scratch = vec![Complex::<f32>::ZERO; data.1.len()];
+        let start = Instant::now();
+        for _ in 0..iters
+        {
+            fft_radix4(data.0, copy.as_mut_slice(), scratch.as_mut_slice());
+        }
+        return start.elapsed();
+    } )
+}
+fn radix4_scalar_bench(b: &mut Bencher, data: &(&Radix4<f32>, &[Complex<f32>]))
+{
+    b.iter_custom(|iters|
+    {
+        let mut copy = vec![Complex::<f32>::ZERO; data.1.len()];
+        copy.copy_from_slice(data.1);
+        let mut scratch = vec![Complex::<f32>::ZERO; data.1.len()];
+        let start = Instant::now();
+        for _ in 0..iters
+        {
+            fft_radix4_scalar(data.0, copy.as_mut_slice(), scratch.as_mut_slice());
+        }
+        return start.elapsed();
+    } )
+}
+
+fn bench_radix4(group: &mut BenchmarkGroup<'_, WallTime>, power: usize)
+{
+    let size = 1 << power;
+    let data = gen_data(size);
+    let mut wn = WCache::<f32>::new(true);
+    wn.ensure_max_power(power);
+    let plan = Radix4::<f32>::new(size, true);
+    
+    group.bench_with_input(
+        BenchmarkId::new("Iterative_V2", size),
+        &(&wn, data.as_ref(), power),
+        it_bench);
+    group.bench_with_input(
+        BenchmarkId::new("Radix4_Scalar", size),
+        &(&plan, data.as_ref()),
+        radix4_scalar_bench);
+    group.bench_with_input(
+        BenchmarkId::new("Radix4", size),
+        &(&plan, data.as_ref()),
+        radix4_bench);
+}
+
+fn real_bench(b: &mut Bencher, data: &(&RealFftPlan<f32>, &[f32]))
+{
+    b.iter_custom(|iters|
+    {
+        let mut out = vec![Complex::<f32>::ZERO; data.0.spectrum_len()];
+        let start = Instant::now();
+        for _ in 0..iters
+        {
+            data.0.forward_into(data.1, out.as_mut_slice());
+        }
+        return start.elapsed();
+    } )
+}
+fn complex_real_bench(b: &mut Bencher, data: &(&FftPlan<f32>, &[f32]))
+{
+    b.iter_custom(|iters|
+    {
+        let start = Instant::now();
+        for _ in 0..iters
+        {
+            // promote to complex as dft_analysis used to
+            let mut copy: Vec<Complex<f32>> = data.1.iter().map(|v| Complex::new(*v, 0.0)).collect();
+            data.0.forward(copy.as_mut_slice());
+        }
+        return start.elapsed();
+    } )
+}
+
+fn bench_real(group: &mut BenchmarkGroup<'_, WallTime>, size: usize)
+{
+    let data: Vec<f32> = (0..size).map(|_| random::<f32>()).collect();
+    let real = RealFftPlan::<f32>::new(size);
+    let complex = FftPlan::<f32>::new(size);
+    
+    group.bench_with_input(
+        BenchmarkId::new("Real", size),
+        &(&real, data.as_ref()),
+        real_bench);
+    group.bench_with_input(
+        BenchmarkId::new("Complex", size),
+        &(&complex, data.as_ref()),
+        complex_real_bench);
+}
+
 fn bench(c: &mut Criterion) {
     let mut group = c.benchmark_group("FFT");
     
@@ -114,6 +261,31 @@ fn bench(c: &mut Criterion) {
     bench_all(&mut group, 10);
     bench_all(&mut group, 16);
     
+    // mixed radix
+    bench_len(&mut group, 300);
+    bench_len(&mut group, 1000);
+    // bluestein
+    bench_len(&mut group, 257);
+    bench_len(&mut group, 1009);
+    
+    group.finish();
+    
+    let mut group = c.benchmark_group("Real FFT");
+    
+    bench_real(&mut group, 256);
+    bench_real(&mut group, 1024);
+    bench_real(&mut group, 1 << 16);
+    bench_real(&mut group, 300);
+    
+    group.finish();
+    
+    let mut group = c.benchmark_group("Radix 4");
+    
+    for power in 4..=20
+    {
+        bench_radix4(&mut group, power);
+    }
+    
     group.finish();
 }
 
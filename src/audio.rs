@@ -0,0 +1,105 @@
+use std::io::{self, Read, Write};
+
+use backend::{estimate_period, loop_wave, resample_period, SampleFormat, Wav};
+
+/// lowest and highest pitch searched for a period
+pub const PITCH_RANGE: (f32, f32) = (20.0, 5000.0);
+/// samples from the start used to find the period
+const ESTIMATE_LEN: usize = 16384;
+
+/// a recording, one period of which fills the wave plot
+#[derive(Debug, Clone, Default)]
+pub struct Audio
+{
+    /// channels averaged
+    pub data: Box<[f32]>,
+    pub sample_rate: u32,
+    pub start: usize,
+    pub period: usize
+}
+
+impl Audio
+{
+    pub fn is_empty(&self) -> bool
+    {
+        return self.data.len() == 0;
+    }
+    pub fn read<R: Read>(&mut self, reader: R) -> io::Result<()>
+    {
+        let wav = Wav::read(reader)?;
+        if wav.is_empty()
+        {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no samples"));
+        }
+        
+        self.data = wav.mono().into_boxed_slice();
+        self.sample_rate = wav.sample_rate.max(1);
+        self.start = 0;
+        self.period = self.max_period();
+        self.detect_period();
+        return Ok(());
+    }
+    
+    /// longest period in samples, at the lowest pitch
+    pub fn max_period(&self) -> usize
+    {
+        let p = (self.sample_rate as f32 / PITCH_RANGE.0) as usize;
+        return p.min(self.data.len()).max(2);
+    }
+    /// last start that leaves a whole period
+    pub fn max_start(&self) -> usize
+    {
+        return self.data.len().saturating_sub(self.period);
+    }
+    pub fn set_start(&mut self, start: usize)
+    {
+        self.start = start.min(self.max_start());
+    }
+    pub fn set_period(&mut self, period: usize)
+    {
+        self.period = period.clamp(2, self.max_period());
+        self.start = self.start.min(self.max_start());
+    }
+    /// period from the autocorrelation after `start`, unchanged if none is found
+    pub fn detect_period(&mut self) -> bool
+    {
+        let end = (self.start + ESTIMATE_LEN).min(self.data.len());
+        let min = (self.sample_rate as f32 / PITCH_RANGE.1) as usize;
+        return match estimate_period(&self.data[self.start..end], min.max(2), self.max_period())
+        {
+            Some(p) =>
+            {
+                self.set_period(p);
+                true
+            },
+            None => false
+        };
+    }
+    pub fn frequency(&self) -> f32
+    {
+        return self.sample_rate as f32 / (self.period.max(1) as f32);
+    }
+    
+    /// selected period stretched over `wave`, scaled to fill -1 to 1
+    pub fn apply(&self, wave: &mut [f32])
+    {
+        if self.is_empty() { return; }
+        
+        let end = (self.start + self.period).min(self.data.len());
+        let period = resample_period(&self.data[self.start..end], wave.len());
+        let peak = period.iter().fold(0.0f32, |a, v| a.max(v.abs()));
+        let scale = if peak > 0.0 { 1.0 / peak } else { 0.0 };
+        for (w, p) in wave.iter_mut().zip(&period)
+        {
+            *w = p * scale;
+        }
+    }
+}
+
+/// `wave` looped at `frequency` for `seconds`, as 16 bit mono
+pub fn write_loop<W: Write>(out: &mut W, wave: &[f32], frequency: f32, sample_rate: u32, seconds: f32) -> io::Result<()>
+{
+    let len = (sample_rate as f32 * seconds) as usize;
+    let data = loop_wave(wave, sample_rate, frequency, len);
+    return Wav::new(sample_rate, 1, SampleFormat::Int16, data).write(out);
+}
\ No newline at end of file
@@ -0,0 +1,199 @@
+use std::f32::consts::TAU;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape
+{
+    Sine,
+    Triangle,
+    Saw,
+    /// parameter is the duty cycle
+    Pulse,
+    /// parameter is the width as a fraction of a period
+    Gaussian,
+    /// parameter is the decay across the plot
+    DampedSine,
+    /// parameter is the end frequency as a multiple of the start
+    Chirp,
+    /// parameter is the number of harmonics
+    Harmonics,
+    /// parameter is the seed, harmonics up to the frequency have random amplitudes and phases
+    Noise
+}
+
+impl Display for Shape
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return f.write_str(match self
+        {
+            Shape::Sine => "Sine",
+            Shape::Triangle => "Triangle",
+            Shape::Saw => "Saw",
+            Shape::Pulse => "Pulse",
+            Shape::Gaussian => "Gaussian",
+            Shape::DampedSine => "Damped sine",
+            Shape::Chirp => "Chirp",
+            Shape::Harmonics => "Harmonics",
+            Shape::Noise => "Noise"
+        });
+    }
+}
+
+/// the shape parameter, its range, step and default
+pub struct Param
+{
+    pub name: &'static str,
+    pub range: RangeInclusive<f32>,
+    pub step: f32,
+    pub default: f32
+}
+
+impl Shape
+{
+    pub const ALL: [Shape; 9] = [Shape::Sine, Shape::Triangle, Shape::Saw, Shape::Pulse, Shape::Gaussian,
+        Shape::DampedSine, Shape::Chirp, Shape::Harmonics, Shape::Noise];
+    
+    pub fn param(&self) -> Option<Param>
+    {
+        return match self
+        {
+            Shape::Sine | Shape::Triangle | Shape::Saw => None,
+            Shape::Pulse => Some(Param { name: "Duty", range: 0.0..=1.0, step: 0.01, default: 0.5 }),
+            Shape::Gaussian => Some(Param { name: "Width", range: 0.01..=0.5, step: 0.01, default: 0.1 }),
+            Shape::DampedSine => Some(Param { name: "Decay", range: 0.0..=20.0, step: 0.1, default: 5.0 }),
+            Shape::Chirp => Some(Param { name: "End", range: 0.0..=32.0, step: 0.5, default: 8.0 }),
+            Shape::Harmonics => Some(Param { name: "Count", range: 1.0..=32.0, step: 1.0, default: 4.0 }),
+            Shape::Noise => Some(Param { name: "Seed", range: 0.0..=1000.0, step: 1.0, default: 0.0 })
+        };
+    }
+}
+
+fn tri(p: f32) -> f32
+{
+    return ((p + 0.25 - (p + 0.75).floor()).abs() * 4.0) - 1.0;
+}
+fn saw(p: f32) -> f32
+{
+    return ((p - p.floor()) * 2.0) - 1.0;
+}
+
+/// fills the wave plot with a shape
+#[derive(Debug, Clone, Copy)]
+pub struct Generator
+{
+    pub shape: Shape,
+    pub amplitude: f32,
+    /// periods across the plot
+    pub frequency: f32,
+    /// fraction of a period
+    pub phase: f32,
+    pub param: f32
+}
+
+impl Default for Generator
+{
+    fn default() -> Self
+    {
+        return Self {
+            shape: Shape::Sine,
+            amplitude: 1.0,
+            frequency: 1.0,
+            phase: 0.0,
+            param: 0.0
+        };
+    }
+}
+
+impl Generator
+{
+    /// changes shape, resetting the parameter
+    pub fn set_shape(&mut self, shape: Shape)
+    {
+        self.shape = shape;
+        self.param = shape.param().map(|p| p.default).unwrap_or(0.0);
+    }
+    
+    /// value at `t`, from 0 to 1 across the plot
+    fn value(&self, t: f32) -> f32
+    {
+        let x = (self.frequency * t) + self.phase;
+        return match self.shape
+        {
+            Shape::Sine => (TAU * x).sin(),
+            Shape::Triangle => tri(x),
+            Shape::Saw => saw(-x),
+            Shape::Pulse => match x - x.floor() < self.param
+            {
+                true => 1.0,
+                false => -1.0
+            },
+            Shape::Gaussian =>
+            {
+                let d = (x - x.floor() - 0.5) / self.param;
+                (-0.5 * d * d).exp()
+            },
+            Shape::DampedSine => (TAU * x).sin() * (-self.param * t).exp(),
+            Shape::Chirp =>
+            {
+                // frequency sweeps linearly from `frequency` to `param` times it
+                let rate = self.frequency * (self.param - 1.0);
+                (TAU * (x + (0.5 * rate * t * t))).sin()
+            },
+            Shape::Harmonics =>
+            {
+                let n = self.param.max(1.0) as usize;
+                (1..=n).map(|k| (TAU * (k as f32) * x).sin() / (k as f32)).sum()
+            },
+            // needs state, see `fill`
+            Shape::Noise => 0.0
+        };
+    }
+    
+    pub fn fill(&self, wave: &mut [f32])
+    {
+        let step = 1.0 / (wave.len() as f32);
+        if self.shape == Shape::Noise
+        {
+            self.noise(wave, step);
+        }
+        else
+        {
+            for (i, v) in wave.iter_mut().enumerate()
+            {
+                *v = self.value(i as f32 * step);
+            }
+        }
+        
+        // sums have no fixed peak
+        if let Shape::Harmonics | Shape::Noise = self.shape
+        {
+            let peak = wave.iter().fold(0.0f32, |a, v| a.max(v.abs()));
+            if peak > 0.0
+            {
+                wave.iter_mut().for_each(|v| *v /= peak);
+            }
+        }
+        
+        wave.iter_mut().for_each(|v| *v = (*v * self.amplitude).clamp(-1.0, 1.0));
+    }
+    /// same seed gives the same noise
+    fn noise(&self, wave: &mut [f32], step: f32)
+    {
+        let mut rng = StdRng::seed_from_u64(self.param as u64);
+        let n = self.frequency.max(1.0) as usize;
+        let parts: Vec<(f32, f32)> = (0..n).map(|_| (rng.random::<f32>(), rng.random::<f32>())).collect();
+        
+        for (i, v) in wave.iter_mut().enumerate()
+        {
+            let x = (i as f32 * step) + self.phase;
+            *v = parts.iter().enumerate().map(|(k, (a, p))|
+            {
+                return a * (TAU * ((((k + 1) as f32) * x) + p)).sin();
+            }).sum();
+        }
+    }
+}
\ No newline at end of file
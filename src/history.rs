@@ -0,0 +1,63 @@
+use std::collections::VecDeque;
+
+/// undo and redo stacks. applying an entry gives the entry that reverses it,
+/// which goes on the other stack
+#[derive(Debug, Clone)]
+pub struct History<T>
+{
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    limit: usize
+}
+
+impl<T> History<T>
+{
+    pub fn new(limit: usize) -> Self
+    {
+        return Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit
+        };
+    }
+    fn push_limited(&mut self, entry: T)
+    {
+        self.undo.push_back(entry);
+        if self.undo.len() > self.limit
+        {
+            self.undo.pop_front();
+        }
+    }
+    /// a new edit, redo is no longer possible
+    pub fn push(&mut self, entry: T)
+    {
+        self.push_limited(entry);
+        self.redo.clear();
+    }
+    pub fn take_undo(&mut self) -> Option<T>
+    {
+        return self.undo.pop_back();
+    }
+    pub fn take_redo(&mut self) -> Option<T>
+    {
+        return self.redo.pop();
+    }
+    /// reverse of an applied redo
+    pub fn push_undo(&mut self, entry: T)
+    {
+        self.push_limited(entry);
+    }
+    /// reverse of an applied undo
+    pub fn push_redo(&mut self, entry: T)
+    {
+        self.redo.push(entry);
+    }
+    pub fn can_undo(&self) -> bool
+    {
+        return self.undo.len() != 0;
+    }
+    pub fn can_redo(&self) -> bool
+    {
+        return self.redo.len() != 0;
+    }
+}
\ No newline at end of file
@@ -2,26 +2,63 @@ mod plot;
 mod screen;
 mod wave_data;
 mod scene;
+mod optimiser;
+mod history;
+mod signal;
+mod spectrogram;
+mod generator;
+mod audio;
 
-use std::f32::consts::{PI, TAU};
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
 
-use backend::{Colour, UIWall, WCache};
-use iced::keyboard::Modifiers;
-use iced::widget::{container, horizontal_rule};
-use iced::{widget::{button, column, container::Style, row, slider, text, toggler, vertical_slider, Space}, Alignment, Background, Color, Element, Length, Padding};
+use backend::{parse, read_csv_columns, Colour, ColourMap, FilterKind, Padding as SpectrumPadding, SourceSpectrum, SpectralFilter, UIWall, Window};
+use iced::futures::channel::mpsc;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::{Subscription, Task};
+use iced::widget::{container, horizontal_rule, scrollable, shader};
+use iced::{widget::{button, column, pick_list, container::Style, row, slider, text, text_input, toggler, vertical_slider, Space}, Alignment, Background, Color, Element, Length, Padding};
 use num::{complex::Complex32, Zero};
-use plot::element::plotter;
+use audio::{write_loop, Audio};
+use generator::{Generator, Shape};
+use history::History;
+use optimiser::{optimise, OptimiseEvent};
+use plot::element::{plotter, AxisScale, Plot};
 use scene::element::MessageFuncs;
-use scene::{Scene, SceneSlit, SceneUIData, SceneUIRef, DEFAULT_WIDTH};
+use scene::fringes::FringeReport;
+use scene::propagate::Solver;
+use scene::ops::{Layout, SceneOp};
+use scene::{Scene, SceneSlit, SceneUIData, SceneUIRef};
 use screen::element::screen;
 use scene::element::scene;
 use screen::renderer::SCREEN_SIZE;
-use wave_data::WaveData;
+use signal::Signal;
+use spectrogram::element::spectrogram;
+use wave_data::{fill, PhaseMode, PlotSnapshot, WaveData, WaveMapping};
 use zene_structs::{Vector2, Vector4};
 
 pub const PLOTTER_SIZE: u32 = 200;
 pub const SPECTRUM_SIZE: u32 = 256;
 pub const SL: f32 = 0.03;
+pub const PROFILE_PATH: &str = "profile.csv";
+pub const MEASURED_PATH: &str = "measured.csv";
+pub const SIGNAL_PATH: &str = "signal.csv";
+pub const AUDIO_PATH: &str = "audio.wav";
+pub const LOOP_PATH: &str = "wave.wav";
+pub const LOOP_RATE: u32 = 44100;
+pub const LOOP_SECONDS: f32 = 2.0;
+pub const MAX_FIT_SLITS: usize = 6;
+pub const OPTIMISE_ITERATIONS: usize = 5000;
+pub const CHIRP_LENGTH: usize = 4096;
+pub const HISTORY_LIMIT: usize = 100;
+pub const DEFAULT_DECIBEL_FLOOR: f32 = -60.0;
+/// padding keeps the spectrum within SPECTRUM_SIZE bins
+pub const PADDINGS: [SpectrumPadding; 3] = [SpectrumPadding::None, SpectrumPadding::Zero(2), SpectrumPadding::Repeat(2)];
 
 const SCENE_MESSAGES: MessageFuncs<Message> = MessageFuncs
 {
@@ -32,6 +69,7 @@ const SCENE_MESSAGES: MessageFuncs<Message> = MessageFuncs
     on_delete: Message::SceneDelete,
     on_cancel: Message::SceneCancel,
     on_drag: Message::SceneDrag,
+    on_drag_end: Message::SceneDragEnd,
     on_ghost: Message::GhostScene,
     on_ghost_end: Message::EndGhostScene,
 };
@@ -40,6 +78,8 @@ const SCENE_MESSAGES: MessageFuncs<Message> = MessageFuncs
 enum Message
 {
     SetScale(f32),
+    ViewDecibels(bool),
+    SetDecibelFloor(f32),
     SetExpo(f32),
     
     PlotSize(usize),
@@ -47,40 +87,171 @@ enum Message
     DragWave(usize, f32),
     PlotFreq(usize, f32),
     DragFreq(usize, f32),
+    EndDragFreq,
     PlotPhase(usize, f32),
     DragPhase(usize, f32),
     
     ViewPhase(bool),
-    FillSine,
-    FillTriangle,
-    FillSaw,
-    FillSquare,
+    SetPhaseMode(PhaseMode),
+    SetPhaseSeed(String),
+    ApplyPhaseSeed,
+    ViewTable(bool),
+    EditCell(usize, Cell, String),
+    EndCellEdit,
+    SetTableFactor(String),
+    ScaleSpectrum,
+    NormaliseSpectrum,
+    ZeroHarmonics(bool),
+    RandomisePhases,
+    Undo,
+    Redo,
+    SetShape(Shape),
+    SetGenAmplitude(f32),
+    SetGenFrequency(f32),
+    SetGenPhase(f32),
+    SetGenParam(f32),
+    Generate,
+    SetFormula(String),
+    FormulaWave,
+    FormulaSpectrum,
     Clear,
     
+    FillSource(SourceSpectrum<f64>),
+    SetTemperature(f32),
+    SetLaserLine(f32),
+    AddLaserLine,
+    SetLineWidth(f32),
+    EndSourceEdit,
+    
+    HarmonicMap(bool),
+    SetFundamental(f32),
+    SetBandMin(f32),
+    SetBandMax(f32),
+    FalseColour(bool),
+    
+    ExportPerWave(bool),
+    ExportProfile,
+    SetFilePath(String),
+    Analyse(bool),
+    FastSolver(bool),
+    
+    SetWindow(Window<f32>),
+    SetKaiserBeta(f32),
+    SetPadding(SpectrumPadding),
+    SetFilterKind(FilterKind),
+    SetCutoff(f32),
+    SetFilterWidth(f32),
+    SetFilterOrder(f32),
+    ApplyFilter,
+    FitMeasured,
+    
+    SignalSize(usize),
+    PlotSignal(usize, f32),
+    DragSignal(usize, f32),
+    FillChirp,
+    LoadSignal,
+    PickSlice(usize),
+    
+    LoadWav,
+    SaveWav,
+    SetLoopPitch(f32),
+    SetAudioStart(f32),
+    SetAudioPeriod(f32),
+    EndAudioEdit,
+    DetectPeriod,
+    
+    TargetSize(usize),
+    PlotTarget(usize, f32),
+    DragTarget(usize, f32),
+    TargetFromScreen,
+    OptimisePhases(bool),
+    StartOptimise,
+    CancelOptimise,
+    Optimise(OptimiseEvent),
+    
     ZoomScene(f32, Vector2),
     PanScene(Vector2),
     SceneHover(SceneUIRef),
     SceneSelect(SceneUIRef),
     SceneDrag(SceneUIRef, Vector2<f64>, Vector2<f64>, Modifiers),
+    SceneDragEnd(),
     SceneDelete(SceneUIRef),
     SceneCancel(),
     GhostScene(usize, f64),
     EndGhostScene(bool)
 }
 
+/// editable column of the harmonic table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell
+{
+    Amplitude,
+    Phase
+}
+
+/// an entry in the undo history, applying one gives its reverse
+#[derive(Debug, Clone)]
+enum Edit
+{
+    Plot(PlotSnapshot),
+    Scene(SceneOp)
+}
+
 #[derive(Debug, Clone)]
 struct State
 {
     plot: WaveData,
-    wn: WCache<f32>,
     last_point: (usize, f32),
     view_phase: bool,
+    view_decibels: bool,
+    /// bottom of the spectrum in dB
+    decibel_floor: f32,
+    /// seed for random phase
+    phase_seed: u64,
+    phase_seed_text: String,
     colours: Box<[Colour]>,
     exposure: f32,
     scene: Scene,
     scene_ui: SceneUIData,
     scene_ref_pos: Vector2<f64>,
     select_wall_old: (Vector2<f64>, Vector2<f64>),
+    /// layout before the current drag, and whether anything has moved
+    drag_start: Option<Layout>,
+    dragged: bool,
+    /// preset the spectrum still follows, cleared by edits made by hand
+    source: Option<SourceSpectrum<f64>>,
+    /// a slider drag on the preset is one edit
+    source_edit: bool,
+    temperature: f32,
+    laser_line: f32,
+    line_width: f32,
+    fundamental: f32,
+    export_per_wave: bool,
+    status: String,
+    /// file read or written by the load and save buttons, each has a default when empty
+    file_path: String,
+    /// existing file that the next save may replace
+    overwrite: Option<String>,
+    fringes: Option<FringeReport>,
+    target: Box<[f32]>,
+    optimise_phases: bool,
+    /// cancel flag and the layout being optimised
+    optimising: Option<(Arc<AtomicBool>, Layout)>,
+    kaiser_beta: f32,
+    signal: Signal,
+    generator: Generator,
+    formula: String,
+    formula_error: Option<String>,
+    audio: Audio,
+    /// a slider drag on the audio selection is one edit
+    audio_edit: bool,
+    loop_pitch: f32,
+    view_table: bool,
+    /// text of the cell being typed in
+    table_cell: Option<(usize, Cell, String)>,
+    table_factor: String,
+    filter: SpectralFilter<f32>,
+    history: History<Edit>
 }
 impl Default for State
 {
@@ -91,17 +262,50 @@ impl Default for State
         
         let scene = Scene::default();
         let scene_ui = SceneUIData::new(&scene, SL, 2.5e-10, Vector2::zero());
+        let phase_seed: u64 = rand::random();
         return Self {
             view_phase: false,
+            view_decibels: false,
+            decibel_floor: DEFAULT_DECIBEL_FLOOR,
+            phase_seed,
+            phase_seed_text: phase_seed.to_string(),
             plot,
-            wn: WCache::<f32>::new(true),
             last_point: Default::default(),
             colours: vec![Colour::ZERO; SCREEN_SIZE as usize].into_boxed_slice(),
             exposure: 1.0,
             scene,
             scene_ui,
             scene_ref_pos: Default::default(),
-            select_wall_old: Default::default()
+            select_wall_old: Default::default(),
+            drag_start: None,
+            dragged: false,
+            source: None,
+            source_edit: false,
+            temperature: 5800.0,
+            laser_line: 632.8,
+            line_width: 2.0,
+            fundamental: 2800.0,
+            export_per_wave: false,
+            status: String::new(),
+            file_path: String::new(),
+            overwrite: None,
+            fringes: None,
+            target: Default::default(),
+            optimise_phases: false,
+            optimising: None,
+            kaiser_beta: 6.0,
+            signal: Signal::default(),
+            generator: Generator::default(),
+            formula: String::new(),
+            formula_error: None,
+            audio: Audio::default(),
+            audio_edit: false,
+            loop_pitch: 220.0,
+            view_table: false,
+            table_cell: None,
+            table_factor: String::from("0.5"),
+            filter: SpectralFilter::new(FilterKind::LowPass, 8.0, 4.0, 2),
+            history: History::new(HISTORY_LIMIT)
         }
     }
 }
@@ -149,156 +353,888 @@ impl State
             _ => return,
         }
         
-        self.scene.simulate(&self.plot.wave_map, &mut self.colours);
+        self.simulate();
+        self.scene_ui.generate_lines(&self.scene, SL);
+    }
+    
+    /// start of an edit to the plot by hand, drags continue the same one.
+    /// the spectrum stops following the preset
+    fn record_plot(&mut self)
+    {
+        self.source = None;
+        self.history.push(Edit::Plot(self.plot.snapshot()));
+    }
+    /// first change of a preset slider drag, the preset is kept
+    fn start_source_edit(&mut self)
+    {
+        if self.source_edit { return; }
+        
+        self.source_edit = true;
+        self.history.push(Edit::Plot(self.plot.snapshot()));
+    }
+    /// applies `op` to the scene and records its reverse
+    fn edit_scene(&mut self, op: SceneOp) -> bool
+    {
+        let reverse = match self.scene.apply(op)
+        {
+            Some(r) => r,
+            None => return false
+        };
+        self.history.push(Edit::Scene(reverse));
+        self.refresh_scene();
+        return true;
+    }
+    /// current layout becomes an undo entry
+    fn record_layout(&mut self)
+    {
+        self.history.push(Edit::Scene(SceneOp::Layout(self.scene.layout())));
+    }
+    /// drops selection and hover that no longer point into the scene
+    fn refresh_scene(&mut self)
+    {
+        if !self.scene.contains_ref(self.scene_ui.selection)
+        {
+            self.scene_ui.selection = SceneUIRef::None;
+        }
+        if !self.scene.contains_ref(self.scene_ui.hover)
+        {
+            self.scene_ui.hover = SceneUIRef::None;
+        }
+        
+        self.simulate();
         self.scene_ui.generate_lines(&self.scene, SL);
     }
+    fn apply_edit(&mut self, edit: Edit) -> Option<Edit>
+    {
+        match edit
+        {
+            Edit::Plot(snapshot) =>
+            {
+                let current = self.plot.snapshot();
+                self.plot.restore(snapshot);
+                // the restored spectrum may not be the preset's
+                self.source = None;
+                self.scene.compute_waves(&self.plot);
+                self.simulate();
+                return Some(Edit::Plot(current));
+            },
+            Edit::Scene(op) =>
+            {
+                let reverse = self.scene.apply(op)?;
+                self.refresh_scene();
+                return Some(Edit::Scene(reverse));
+            }
+        }
+    }
+    fn undo(&mut self, redo: bool)
+    {
+        // not while a drag is in progress
+        if self.drag_start.is_some() { return; }
+        self.table_cell = None;
+        
+        let entry = match redo
+        {
+            true => self.history.take_redo(),
+            false => self.history.take_undo()
+        };
+        // entries that no longer fit are dropped
+        let reverse = match entry.and_then(|e| self.apply_edit(e))
+        {
+            Some(r) => r,
+            None => return
+        };
+        match redo
+        {
+            true => self.history.push_undo(reverse),
+            false => self.history.push_redo(reverse)
+        }
+    }
+    
+    /// evaluates the formula over t from 0 to 1 across the wave,
+    /// or over the wavelength l in nm across the spectrum
+    fn apply_formula(&mut self, spectrum: bool) -> Result<(), String>
+    {
+        let var = match spectrum
+        {
+            true => "l",
+            false => "t"
+        };
+        let expr = parse(&self.formula, &[var]).map_err(|e| e.to_string())?;
+        // undefined points are zero
+        let value = |x: f64, min: f64| -> f32
+        {
+            let v = expr.eval(&[x]);
+            if v.is_nan() { return 0.0; }
+            return v.clamp(min, 1.0) as f32;
+        };
+        
+        self.record_plot();
+        if spectrum
+        {
+            let amps: Vec<f32> = self.plot.wave_map.iter().map(|w| value(w.0, 0.0)).collect();
+            self.plot.set_spectrum(&amps);
+            self.plot.compute_plot();
+        }
+        else
+        {
+            let step = 1.0 / (self.plot.wave.len() as f64);
+            for (i, v) in self.plot.wave.iter_mut().enumerate()
+            {
+                *v = value(i as f64 * step, -1.0);
+            }
+            self.plot.compute_dft();
+        }
+        
+        self.scene.compute_waves(&self.plot);
+        self.simulate();
+        return Ok(());
+    }
+    
+    fn fill_source(&mut self)
+    {
+        let source = match &self.source
+        {
+            Some(s) => s,
+            None => return
+        };
+        
+        self.plot.apply_source(source, self.line_width as f64);
+        self.plot.compute_plot();
+        
+        self.scene.compute_waves(&self.plot);
+        self.simulate();
+    }
+    
+    fn set_band(&mut self, band: (f64, f64))
+    {
+        let r = self.scene.set_band(band);
+        // same view of the scaled scene
+        self.scene_ui.zoom /= r as f32;
+        self.scene_ui.generate_lines(&self.scene, SL);
+        self.plot.set_band(band);
+        self.refresh_wave_map();
+    }
+    fn set_mapping(&mut self, mapping: WaveMapping)
+    {
+        self.plot.set_mapping(mapping);
+        self.refresh_wave_map();
+    }
+    /// the typed file, or `default` when there is none
+    fn path(&self, default: &str) -> String
+    {
+        let path = self.file_path.trim();
+        if path.is_empty() { return String::from(default); }
+        return String::from(path);
+    }
+    /// an existing file is only replaced when saved to twice in a row
+    fn confirm_overwrite(&mut self, path: &str) -> bool
+    {
+        if !Path::new(path).exists() || self.overwrite.as_deref() == Some(path)
+        {
+            self.overwrite = None;
+            return true;
+        }
+        
+        self.overwrite = Some(String::from(path));
+        self.status = format!("{path} exists, save again to overwrite");
+        return false;
+    }
+    fn export_profile(&self, path: &str) -> std::io::Result<()>
+    {
+        let mut out = BufWriter::new(File::create(path)?);
+        return self.scene.write_profile_csv(&mut out, SCREEN_SIZE as usize, self.export_per_wave);
+    }
+    /// wall of the selected slit, wall or point
+    fn selected_wall(&self) -> Option<usize>
+    {
+        return match self.scene_ui.selection
+        {
+            SceneUIRef::Wall(i) | SceneUIRef::Slit(i, _) | SceneUIRef::Point(i, _) => Some(i),
+            _ => None
+        };
+    }
+    fn fit_measured(&mut self) -> Result<String, String>
+    {
+        let wall = self.selected_wall().ok_or_else(|| String::from("Select a wall to fit"))?;
+        let file = File::open(self.path(MEASURED_PATH)).map_err(|e| e.to_string())?;
+        let measured: Vec<(f64, f64)> = read_csv_columns(BufReader::new(file), 0, 1)
+            .map_err(|e| e.to_string())?;
+        
+        let distance = self.scene.screen_distance(wall);
+        let fit = self.scene.fit_measured(wall, &measured, MAX_FIT_SLITS)
+            .ok_or(String::from("no fit found"))?;
+        self.record_layout();
+        self.scene.apply_fit(wall, &fit, distance);
+        
+        self.simulate();
+        self.scene_ui.generate_lines(&self.scene, SL);
+        
+        return Ok(format!("{} slits, a: {:.0}±{:.0}nm, d: {:.0}±{:.0}nm",
+            fit.count, fit.width, fit.width_err, fit.separation, fit.separation_err));
+    }
+    fn start_optimise(&mut self) -> Task<Message>
+    {
+        if self.optimising.is_some() { return Task::none(); }
+        
+        let wall = match self.selected_wall()
+        {
+            Some(w) => w,
+            None =>
+            {
+                self.status = String::from("Select a wall to optimise");
+                return Task::none();
+            }
+        };
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.optimising = Some((cancel.clone(), self.scene.layout()));
+        self.status = String::from("Optimising");
+        
+        let scene = self.scene.clone();
+        let target: Vec<f64> = self.target.iter().map(|t| *t as f64).collect();
+        let phases = self.optimise_phases;
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move ||
+        {
+            let slits = optimise(scene, wall, &target, phases, OPTIMISE_ITERATIONS, cancel, |i, c|
+            {
+                if i % 20 != 0 { return; }
+                let _ = sender.unbounded_send(OptimiseEvent::Step(i, c));
+            });
+            let _ = sender.unbounded_send(OptimiseEvent::Done(wall, slits));
+        });
+        
+        return Task::run(receiver, Message::Optimise);
+    }
+    fn reanalyse(&mut self)
+    {
+        self.plot.compute_dft();
+        self.signal.compute(&self.plot);
+        self.scene.compute_waves(&self.plot);
+        self.simulate();
+    }
+    /// the selected slice of a changed signal drives the spectrum
+    fn update_signal(&mut self)
+    {
+        self.signal.compute(&self.plot);
+        let slice = self.signal.slice;
+        if self.signal.select(slice, &mut self.plot)
+        {
+            self.source = None;
+            self.reanalyse();
+        }
+    }
+    fn load_signal(&mut self) -> std::io::Result<()>
+    {
+        let file = File::open(self.path(SIGNAL_PATH))?;
+        return self.signal.read_csv(BufReader::new(file));
+    }
+    fn load_wav(&mut self) -> std::io::Result<()>
+    {
+        let file = File::open(self.path(AUDIO_PATH))?;
+        return self.audio.read(BufReader::new(file));
+    }
+    fn save_wav(&self, path: &str) -> std::io::Result<()>
+    {
+        let mut out = BufWriter::new(File::create(path)?);
+        return write_loop(&mut out, &self.plot.wave, self.loop_pitch, LOOP_RATE, LOOP_SECONDS);
+    }
+    /// selected period of the audio becomes the wave
+    fn apply_audio(&mut self)
+    {
+        if self.audio.is_empty() { return; }
+        
+        self.audio.apply(&mut self.plot.wave);
+        self.plot.compute_dft();
+        self.scene.compute_waves(&self.plot);
+        self.simulate();
+    }
+    /// vertical scale of the spectrum plot
+    fn spec_axis(&self) -> AxisScale
+    {
+        return match self.view_decibels
+        {
+            true => AxisScale::Decibel(self.decibel_floor),
+            false => AxisScale::Linear
+        };
+    }
+    /// the fringes are reanalysed with the screen, when shown
+    fn simulate(&mut self)
+    {
+        self.scene.simulate(&self.plot.wave_map, &mut self.colours);
+        if self.fringes.is_some()
+        {
+            self.fringes = Some(self.scene.fringe_report(SCREEN_SIZE as usize));
+        }
+    }
+    /// after the spectrum or phase is changed directly
+    fn spectrum_edited(&mut self)
+    {
+        self.plot.compute_plot();
+        self.scene.compute_waves(&self.plot);
+        self.simulate();
+    }
+    /// typing into a new cell starts an edit, later keys continue it
+    fn edit_cell(&mut self, i: usize, cell: Cell, text: String)
+    {
+        let same = matches!(&self.table_cell, Some((j, c, _)) if *j == i && *c == cell);
+        if !same
+        {
+            self.record_plot();
+        }
+        
+        let value = text.trim().parse::<f32>();
+        self.table_cell = Some((i, cell, text));
+        // kept as text until it parses
+        let value = match value
+        {
+            Ok(v) if v.is_finite() && i < self.plot.spectrum.len() => v,
+            _ => return
+        };
+        match cell
+        {
+            Cell::Amplitude => self.plot.set_amplitude(i, value.max(0.0)),
+            Cell::Phase => self.plot.set_phase_point(i, value)
+        }
+        self.spectrum_edited();
+    }
+    /// first change of a slider drag
+    fn start_audio_edit(&mut self)
+    {
+        if self.audio_edit { return; }
+        
+        self.audio_edit = true;
+        self.record_plot();
+    }
+    fn refresh_wave_map(&mut self)
+    {
+        self.signal.compute(&self.plot);
+        // amplitudes are kept, so a spectrum still from a preset is resampled
+        if self.source.is_some()
+        {
+            self.fill_source();
+            return;
+        }
+        
+        self.scene.compute_waves(&self.plot);
+        self.simulate();
+    }
 }
 
-fn tri(p: f32) -> f32
-{
-    return ((p + 0.25 - (p + 0.75).floor()).abs() * 4.0) - 1.0;
-}
-fn saw(p: f32) -> f32
+fn update(state: &mut State, message: Message) -> Task<Message>
 {
-    return ((p - p.floor()) * 2.0) - 1.0;
-}
-fn square(p: f32) -> f32
-{
-    return ((((p * 2.0) as isize % 2) * 2) - 1) as f32;
+    let task = match message
+    {
+        Message::StartOptimise => state.start_optimise(),
+        m =>
+        {
+            update_state(state, m);
+            Task::none()
+        }
+    };
+    return task;
 }
 
-fn update(state: &mut State, message: Message)
+fn update_state(state: &mut State, message: Message)
 {
     match message
     {
         Message::SetScale(v) => state.plot.set_scale(v),
+        Message::ViewDecibels(v) => state.view_decibels = v,
+        Message::SetDecibelFloor(v) => state.decibel_floor = v,
         Message::SetExpo(v) => state.exposure = v,
-        Message::ViewPhase(v) =>
+        Message::ViewPhase(v) => state.view_phase = v,
+        Message::SetPhaseMode(mode) =>
         {
-            state.view_phase = v;
-            state.plot.set_phase(v);
-        }
+            if let PhaseMode::Random(seed) = mode
+            {
+                state.phase_seed = seed;
+            }
+            state.record_plot();
+            state.plot.set_phase_mode(mode);
+            state.spectrum_edited();
+        },
+        Message::SetPhaseSeed(text) => state.phase_seed_text = text,
+        Message::ApplyPhaseSeed =>
+        {
+            let seed = match state.phase_seed_text.trim().parse::<u64>()
+            {
+                Ok(s) => s,
+                Err(_) =>
+                {
+                    state.status = String::from("Seed must be a whole number");
+                    return;
+                }
+            };
+            state.phase_seed = seed;
+            // random phase is redrawn with the new seed
+            if let PhaseMode::Random(_) = state.plot.get_phase_mode()
+            {
+                state.record_plot();
+                state.plot.set_phase_mode(PhaseMode::Random(seed));
+                state.spectrum_edited();
+            }
+        },
+        Message::ViewTable(v) =>
+        {
+            state.view_table = v;
+            state.table_cell = None;
+        },
+        Message::EditCell(i, cell, text) => state.edit_cell(i, cell, text),
+        Message::EndCellEdit => state.table_cell = None,
+        Message::SetTableFactor(text) => state.table_factor = text,
+        Message::ScaleSpectrum =>
+        {
+            let factor = match state.table_factor.trim().parse::<f32>()
+            {
+                Ok(f) if f.is_finite() && f >= 0.0 => f,
+                _ =>
+                {
+                    state.status = String::from("Scale must be a positive number");
+                    return;
+                }
+            };
+            state.record_plot();
+            state.plot.scale_spectrum(factor);
+            state.spectrum_edited();
+        },
+        Message::NormaliseSpectrum =>
+        {
+            state.record_plot();
+            state.plot.normalise_spectrum(1.0);
+            state.spectrum_edited();
+        },
+        Message::ZeroHarmonics(odd) =>
+        {
+            state.record_plot();
+            state.plot.zero_harmonics(odd);
+            state.spectrum_edited();
+        },
+        Message::RandomisePhases =>
+        {
+            state.record_plot();
+            state.plot.randomise_phases(&mut rand::rng());
+            state.spectrum_edited();
+        },
         Message::PlotSize(size) =>
         {
             state.plot.resize(size);
-            state.plot.compute_dft(&mut state.wn);
+            state.plot.compute_dft();
+            state.signal.compute(&state.plot);
             
             state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
         },
         Message::PlotWave(i, v) =>
         {
+            state.record_plot();
             state.plot.set_plot_point(i, v);
-            state.plot.compute_dft(&mut state.wn);
+            state.plot.compute_dft();
             state.last_point = (i, v);
             
             state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
         },
         Message::DragWave(i, v) =>
         {
             state.plot.set_plot_line(state.last_point, (i, v));
-            state.plot.compute_dft(&mut state.wn);
+            state.plot.compute_dft();
             state.last_point = (i, v);
             
             state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
         },
         Message::PlotFreq(i, v) =>
         {
+            state.record_plot();
             state.plot.set_spec_point(i, v);
-            state.plot.compute_plot(&mut state.wn);
+            state.plot.compute_plot();
             state.last_point = (i, v);
             
             state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
         },
         Message::DragFreq(i, v) =>
         {
-            state.plot.set_spec_line(state.last_point, (i, v));
-            state.plot.compute_plot(&mut state.wn);
+            // interpolated in the plot's axis, so decibel drags follow the cursor
+            let axis = state.spec_axis();
+            let height = |(i, v): (usize, f32)| (i, axis.height(v));
+            state.plot.set_spec_line(height(state.last_point), height((i, v)), |h| axis.value(h));
+            state.plot.compute_plot();
             state.last_point = (i, v);
             
             state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
+        },
+        Message::EndDragFreq =>
+        {
+            if state.plot.finish_spec_line()
+            {
+                state.spectrum_edited();
+            }
         },
         Message::PlotPhase(i, v) =>
         {
+            state.record_plot();
             state.plot.set_phase_point(i, v);
-            state.plot.compute_plot(&mut state.wn);
+            state.plot.compute_plot();
             state.last_point = (i, v);
             
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
         },
         Message::DragPhase(i, v) =>
         {
             state.plot.set_phase_line(state.last_point, (i, v));
-            state.plot.compute_plot(&mut state.wn);
+            state.plot.compute_plot();
             state.last_point = (i, v);
             
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
         },
+        Message::Undo => state.undo(false),
+        Message::Redo => state.undo(true),
         Message::Clear =>
         {
+            state.record_plot();
             state.plot.wave.fill(0.0);
             state.plot.dft.fill(Complex32::ZERO);
             state.plot.update_spec_phase();
             
             state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
+        },
+        Message::SetShape(shape) => state.generator.set_shape(shape),
+        Message::SetGenAmplitude(v) => state.generator.amplitude = v,
+        Message::SetGenFrequency(v) => state.generator.frequency = v,
+        Message::SetGenPhase(v) => state.generator.phase = v,
+        Message::SetGenParam(v) => state.generator.param = v,
+        Message::Generate =>
+        {
+            state.record_plot();
+            state.generator.fill(&mut state.plot.wave);
+            state.plot.compute_dft();
+            state.scene.compute_waves(&state.plot);
+            state.simulate();
+        },
+        Message::SetFormula(formula) =>
+        {
+            state.formula = formula;
+            state.formula_error = None;
         },
-        Message::FillSine =>
+        Message::FormulaWave => state.formula_error = state.apply_formula(false).err(),
+        Message::FormulaSpectrum => state.formula_error = state.apply_formula(true).err(),
+        Message::FillSource(source) =>
         {
-            let step =  TAU / (state.plot.wave.len() as f32);
-            let mut t = 0.0f32;
-            for v in state.plot.wave.iter_mut()
+            state.record_plot();
+            state.source = Some(source);
+            state.fill_source();
+        },
+        Message::SetTemperature(v) =>
+        {
+            state.temperature = v;
+            if let Some(SourceSpectrum::Blackbody(_)) = &state.source
             {
-                *v = t.sin();
-                t += step;
+                state.start_source_edit();
+                state.source = Some(SourceSpectrum::Blackbody(v as f64));
+                state.fill_source();
             }
-            state.plot.compute_dft(&mut state.wn);
-            state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
         },
-        Message::FillTriangle =>
+        Message::SetLaserLine(v) =>
         {
-            let step =  1.0 / (state.plot.wave.len() as f32);
-            let mut t = 0.0f32;
-            for v in state.plot.wave.iter_mut()
+            state.laser_line = v;
+            if let Some(SourceSpectrum::Laser(_)) = &state.source
             {
-                *v = tri(t);
-                t += step;
+                state.start_source_edit();
+                // edit the most recently added line
+                if let Some(SourceSpectrum::Laser(lines)) = &mut state.source
+                {
+                    if let Some(l) = lines.last_mut()
+                    {
+                        *l = v as f64;
+                    }
+                }
+                state.fill_source();
             }
-            state.plot.compute_dft(&mut state.wn);
-            state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
         },
-        Message::FillSaw =>
+        Message::AddLaserLine =>
         {
-            let step =  1.0 / (state.plot.wave.len() as f32);
-            let mut t = 0.0f32;
-            for v in state.plot.wave.iter_mut()
+            state.history.push(Edit::Plot(state.plot.snapshot()));
+            match &mut state.source
             {
-                *v = saw(1.0 - t);
-                t += step;
+                Some(SourceSpectrum::Laser(lines)) => lines.push(state.laser_line as f64),
+                _ => state.source = Some(SourceSpectrum::Laser(vec![state.laser_line as f64]))
             }
-            state.plot.compute_dft(&mut state.wn);
-            state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.fill_source();
         },
-        Message::FillSquare =>
+        Message::SetLineWidth(v) =>
         {
-            let step =  1.0 / (state.plot.wave.len() as f32);
-            let mut t = 0.0f32;
-            for v in state.plot.wave.iter_mut()
+            state.line_width = v;
+            if state.source.is_some()
             {
-                *v = square(1.0 - t);
-                t += step;
+                state.start_source_edit();
+                state.fill_source();
             }
-            state.plot.compute_dft(&mut state.wn);
-            state.scene.compute_waves(&state.plot);
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
-        }
+        },
+        Message::EndSourceEdit => state.source_edit = false,
+        Message::HarmonicMap(v) =>
+        {
+            let mapping = match v
+            {
+                true => WaveMapping::Harmonic(state.fundamental as f64),
+                false => WaveMapping::Linear
+            };
+            state.set_mapping(mapping);
+        },
+        Message::SetFundamental(v) =>
+        {
+            state.fundamental = v;
+            if let WaveMapping::Harmonic(_) = state.plot.get_mapping()
+            {
+                state.set_mapping(WaveMapping::Harmonic(v as f64));
+            }
+        },
+        Message::SetBandMin(v) =>
+        {
+            let band = state.plot.get_band();
+            state.set_band(((v as f64).min(band.1 - 10.0), band.1));
+        },
+        Message::SetBandMax(v) =>
+        {
+            let band = state.plot.get_band();
+            state.set_band((band.0, (v as f64).max(band.0 + 10.0)));
+        },
+        Message::FalseColour(v) =>
+        {
+            let map = match v
+            {
+                true => ColourMap::FalseColour,
+                false => ColourMap::Visible
+            };
+            state.plot.set_colour_map(map);
+            state.refresh_wave_map();
+        },
+        Message::ExportPerWave(v) => state.export_per_wave = v,
+        Message::Analyse(v) =>
+        {
+            state.fringes = match v
+            {
+                true => Some(state.scene.fringe_report(SCREEN_SIZE as usize)),
+                false => None
+            };
+        },
+        Message::SetWindow(window) =>
+        {
+            state.plot.set_window(window);
+            state.reanalyse();
+        },
+        Message::SetKaiserBeta(beta) =>
+        {
+            state.kaiser_beta = beta;
+            if let Window::Kaiser(_) = state.plot.get_window()
+            {
+                state.plot.set_window(Window::Kaiser(beta));
+                state.reanalyse();
+            }
+        },
+        Message::SetPadding(padding) =>
+        {
+            state.plot.set_padding(padding);
+            state.reanalyse();
+        },
+        Message::SetFilterKind(kind) => state.filter.kind = kind,
+        Message::SetCutoff(v) => state.filter.cutoff = v,
+        Message::SetFilterWidth(v) => state.filter.width = v,
+        Message::SetFilterOrder(v) => state.filter.order = v as u32,
+        Message::ApplyFilter =>
+        {
+            state.record_plot();
+            state.plot.apply_filter(&state.filter);
+            state.spectrum_edited();
+        },
+        Message::SignalSize(width) => state.signal.update_view(width),
+        Message::PlotSignal(i, v) =>
+        {
+            state.signal.set_point(i, v);
+            state.last_point = (i, v);
+            state.update_signal();
+        },
+        Message::DragSignal(i, v) =>
+        {
+            state.signal.set_line(state.last_point, (i, v));
+            state.last_point = (i, v);
+            state.update_signal();
+        },
+        Message::FillChirp =>
+        {
+            // a few cycles per frame up to near nyquist
+            let start = 2.0 / (state.plot.wave.len().max(1) as f32);
+            state.signal.chirp(CHIRP_LENGTH, start, 0.45);
+            state.update_signal();
+        },
+        Message::LoadSignal =>
+        {
+            state.status = match state.load_signal()
+            {
+                Ok(()) => format!("Loaded {} samples", state.signal.data.len()),
+                Err(e) => format!("Load failed: {e}")
+            };
+            state.update_signal();
+        },
+        Message::PickSlice(i) =>
+        {
+            if state.signal.select(i, &mut state.plot)
+            {
+                state.source = None;
+                state.reanalyse();
+            }
+        },
+        Message::LoadWav =>
+        {
+            if let Err(e) = state.load_wav()
+            {
+                state.status = format!("Load failed: {e}");
+                return;
+            }
+            state.status = format!("Loaded {} samples at {}Hz, period {:.1}Hz",
+                state.audio.data.len(), state.audio.sample_rate, state.audio.frequency());
+            
+            state.record_plot();
+            state.apply_audio();
+        },
+        Message::SaveWav =>
+        {
+            let path = state.path(LOOP_PATH);
+            if !state.confirm_overwrite(&path) { return; }
+            
+            state.status = match state.save_wav(&path)
+            {
+                Ok(()) => format!("Saved {path}"),
+                Err(e) => format!("Save failed: {e}")
+            };
+        },
+        Message::SetLoopPitch(v) => state.loop_pitch = v,
+        Message::SetAudioStart(v) =>
+        {
+            state.start_audio_edit();
+            state.audio.set_start(v as usize);
+            state.apply_audio();
+        },
+        Message::SetAudioPeriod(v) =>
+        {
+            state.start_audio_edit();
+            state.audio.set_period(v as usize);
+            state.apply_audio();
+        },
+        Message::EndAudioEdit => state.audio_edit = false,
+        Message::DetectPeriod =>
+        {
+            if !state.audio.detect_period()
+            {
+                state.status = String::from("No period found");
+                return;
+            }
+            state.record_plot();
+            state.apply_audio();
+        },
+        Message::FastSolver(v) =>
+        {
+            state.scene.set_solver(match v
+            {
+                true => Solver::AngularSpectrum,
+                false => Solver::Direct
+            });
+            state.status = match v && !state.scene.can_propagate()
+            {
+                true => String::from("Scene unsuited to FFT solver, using direct"),
+                false => String::new()
+            };
+            state.simulate();
+        },
+        Message::ExportProfile =>
+        {
+            let path = state.path(PROFILE_PATH);
+            if !state.confirm_overwrite(&path) { return; }
+            
+            state.status = match state.export_profile(&path)
+            {
+                Ok(()) => format!("Saved {path}"),
+                Err(e) => format!("Export failed: {e}")
+            };
+        },
+        Message::SetFilePath(path) =>
+        {
+            state.file_path = path;
+            state.overwrite = None;
+        },
+        Message::FitMeasured =>
+        {
+            state.status = match state.fit_measured()
+            {
+                Ok(s) => s,
+                Err(e) => format!("Fit failed: {e}")
+            };
+        },
+        Message::TargetSize(size) =>
+        {
+            let mut new = vec![0.0; size].into_boxed_slice();
+            for (n, t) in new.iter_mut().zip(state.target.iter())
+            {
+                *n = *t;
+            }
+            state.target = new;
+        },
+        Message::PlotTarget(i, v) =>
+        {
+            state.target[i] = v.clamp(0.0, 1.0);
+            state.last_point = (i, v);
+        },
+        Message::DragTarget(i, v) =>
+        {
+            fill(&mut state.target, state.last_point, (i, v.clamp(0.0, 1.0)));
+            state.last_point = (i, v.clamp(0.0, 1.0));
+        },
+        Message::TargetFromScreen =>
+        {
+            let profile = state.scene.generate_profile(state.target.len(), false);
+            let max = profile.total.iter().fold(0.0f64, |a, b| a.max(*b));
+            if max <= 0.0 { return; }
+            for (t, v) in state.target.iter_mut().zip(&profile.total)
+            {
+                *t = (v / max) as f32;
+            }
+        },
+        Message::OptimisePhases(v) => state.optimise_phases = v,
+        // handled in update
+        Message::StartOptimise => {},
+        Message::CancelOptimise =>
+        {
+            if let Some((cancel, _)) = &state.optimising
+            {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        },
+        Message::Optimise(OptimiseEvent::Step(i, cost)) =>
+        {
+            state.status = format!("Optimising {i}: {cost:.5}");
+        },
+        Message::Optimise(OptimiseEvent::Done(wall, slits)) =>
+        {
+            let layout = match state.optimising.take()
+            {
+                Some((_, l)) => l,
+                None => return
+            };
+            // scene changed while running
+            if !layout.matches(&state.scene)
+            {
+                state.status = String::from("Scene changed, optimised layout discarded");
+                return;
+            }
+            
+            state.status = String::from("Optimised");
+            state.record_layout();
+            state.scene.set_slits(wall, slits);
+            state.simulate();
+            state.scene_ui.generate_lines(&state.scene, SL);
+        },
         Message::ZoomScene(zoom, pan) =>
         {
             state.scene_ui.zoom = zoom;
@@ -326,6 +1262,13 @@ fn update(state: &mut State, message: Message)
             {
                 state.select_wall_old = state.scene.env.screen;
             }
+            // a press on something starts a drag, which ends on release
+            state.drag_start = match scene_uiref
+            {
+                SceneUIRef::None => None,
+                _ => Some(state.scene.layout())
+            };
+            state.dragged = false;
             
             if state.scene_ui.selection == scene_uiref { return; }
             state.scene_ui.selection = scene_uiref;
@@ -333,15 +1276,17 @@ fn update(state: &mut State, message: Message)
         },
         Message::SceneDelete(scene_uiref) =>
         {
-            match scene_uiref
+            let op = match scene_uiref
             {
                 SceneUIRef::None => return,
-                SceneUIRef::Slit(wall, slit) => state.scene.delete_slit(wall, slit),
-                SceneUIRef::Wall(wall) => state.scene.delete_wall(wall),
+                SceneUIRef::Slit(wall, slit) => SceneOp::DeleteSlit(wall, slit),
+                SceneUIRef::Wall(wall) => SceneOp::DeleteWall(wall),
                 SceneUIRef::Point(_, _) => return,
                 SceneUIRef::ScreenPoint(_) => return,
                 SceneUIRef::Screen => return,
-            }
+            };
+            // may be out of date after an undo
+            if !state.edit_scene(op) { return; }
             
             if state.scene_ui.selection == scene_uiref
             {
@@ -356,12 +1301,29 @@ fn update(state: &mut State, message: Message)
         },
         Message::SceneDrag(scene_uiref, pp, wp, mods) =>
         {
+            // element can still hold a selection removed by an undo
+            if !state.scene.contains_ref(scene_uiref) { return; }
+            
+            state.dragged = true;
             state.drag_scene(scene_uiref, pp, wp, mods);
+        },
+        Message::SceneDragEnd() =>
+        {
+            // one entry for the whole drag
+            if let Some(layout) = state.drag_start.take()
+            {
+                if state.dragged
+                {
+                    state.history.push(Edit::Scene(SceneOp::Layout(layout)));
+                }
+            }
+            state.dragged = false;
         }
         Message::GhostScene(i, p) =>
         {
-            let p = p.clamp(DEFAULT_WIDTH * 0.5, state.scene.get_wall(i).len() - (DEFAULT_WIDTH * 0.5));
-            let ghost = (SceneSlit { width: DEFAULT_WIDTH, position: p }, i);
+            let width = state.scene.default_width();
+            let p = p.clamp(width * 0.5, state.scene.get_wall(i).len() - (width * 0.5));
+            let ghost = (SceneSlit { width, position: p, phase: 0.0 }, i);
             state.scene_ui.ghost = Some(ghost);
             
             state.scene.simulate_ghost(&state.plot.wave_map, &mut state.colours, ghost);
@@ -369,32 +1331,111 @@ fn update(state: &mut State, message: Message)
         },
         Message::EndGhostScene(valid) =>
         {
+            let ghost = state.scene_ui.ghost.take();
             if valid
             {
                 // will exist
-                let ghost = state.scene_ui.ghost.unwrap();
-                state.scene.insert_slit(ghost);
+                let ghost = ghost.unwrap();
+                if state.edit_scene(SceneOp::InsertSlit(ghost.1, ghost.0)) { return; }
             }
             
-            state.scene_ui.ghost = None;
-            state.scene.simulate(&state.plot.wave_map, &mut state.colours);
+            state.simulate();
             state.scene_ui.generate_lines(&state.scene, SL);
         },
         Message::SceneCancel() =>
         {
-            let old = state.scene_ref_pos;
-            state.drag_scene(state.scene_ui.selection, old, old, Modifiers::empty());
+            // put back everything the drag moved
+            if let Some(layout) = state.drag_start.take()
+            {
+                state.scene.apply(SceneOp::Layout(layout));
+            }
+            state.dragged = false;
+            state.refresh_scene();
         }
     }
 }
 
+/// every bin with its wavelength, amplitude before the display scale and phase
+fn harmonic_table(state: &State) -> Element<Message>
+{
+    let plot = &state.plot;
+    let cell = |i: usize, cell: Cell, value: f32| -> Element<Message>
+    {
+        let content = match &state.table_cell
+        {
+            Some((j, c, text)) if *j == i && *c == cell => text.clone(),
+            _ => format!("{value:.4}")
+        };
+        return text_input("", &content)
+            .on_input(move |t| Message::EditCell(i, cell, t))
+            .on_submit(Message::EndCellEdit)
+            .width(Length::Fixed(80.0)).size(12).into();
+    };
+    
+    let rows = plot.phase.iter().zip(plot.wave_map.iter()).enumerate()
+        .map(|(i, (p, w))| -> Element<Message>
+        {
+            return row![
+                text(format!("{}", i + 1)).width(Length::Fixed(30.0)).size(12),
+                text(format!("{:.1}nm", w.0)).width(Length::Fixed(60.0)).size(12),
+                cell(i, Cell::Amplitude, plot.get_amplitude(i)),
+                cell(i, Cell::Phase, *p)
+            ].spacing(5).align_y(Alignment::Center).into();
+        });
+    
+    return column![
+        row![
+            text("k").width(Length::Fixed(30.0)),
+            text("λ").width(Length::Fixed(60.0)),
+            text("Amplitude").width(Length::Fixed(80.0)),
+            text("Phase").width(Length::Fixed(80.0))
+        ].spacing(5),
+        scrollable(column(rows).spacing(2)).height(Length::Fixed(200.0)),
+        row![
+            text_input("", &state.table_factor).on_input(Message::SetTableFactor)
+                .on_submit(Message::ScaleSpectrum).width(Length::Fixed(50.0)),
+            button("Scale").on_press(Message::ScaleSpectrum),
+            button("Normalise").on_press(Message::NormaliseSpectrum)
+        ].spacing(5).align_y(Alignment::Center),
+        row![
+            button("Zero odd").on_press(Message::ZeroHarmonics(true)),
+            button("Zero even").on_press(Message::ZeroHarmonics(false)),
+            button("Random phase").on_press(Message::RandomisePhases)
+        ].spacing(5).align_y(Alignment::Center)
+    ].spacing(5).into();
+}
+
+fn fringe_panel(report: &FringeReport) -> Element<Message>
+{
+    let analysis = &report.analysis;
+    // scene lengths are in nm
+    let mut panel = column![
+        text("Measured").size(18),
+        text(format!("Maxima: {}", analysis.maxima.len())),
+        text(format!("Spacing: {:.4}mm", analysis.spacing * 1e-6)),
+        text(format!("Visibility: {:.3}", analysis.visibility))
+    ].spacing(5);
+    
+    if let Some(theory) = &report.theory
+    {
+        panel = panel.push(text("Theory").size(18))
+            .push(text(format!("λ: {:.1}nm", theory.wavelength)))
+            .push(text(format!("d: {:.1}nm, a: {:.1}nm", theory.separation, theory.width)))
+            .push(text(format!("Maxima: {}", report.theory_maxima.len())))
+            .push(text(format!("Spacing: {:.4}mm", theory.fringe_spacing() * 1e-6)))
+            .push(text(format!("Slit minima: {}", report.theory_minima.len())));
+    }
+    
+    return panel.width(Length::Fixed(180.0)).into();
+}
+
 fn view(state: &State) -> Element<Message>
 {
     let plot = &state.plot;
     let phase_el: Element<Message> = if state.view_phase
     {
         plotter::<fn(usize) -> Message, _, _, _, _, 2>(None, Message::PlotPhase, Message::DragPhase,
-            &plot.phase, -PI..PI, Vector4::new(0.0, 1.0, 1.0, 1.0))
+            &plot.phase, -PI..PI, AxisScale::Linear, Vector4::new(0.0, 1.0, 1.0, 1.0))
             .width(Length::Fixed(SPECTRUM_SIZE as f32)).into()
     }
     else
@@ -402,17 +1443,135 @@ fn view(state: &State) -> Element<Message>
         Space::new(Length::Fixed(0.0), Length::Fixed(0.0)).into()
     };
     
+    let filter = &state.filter;
+    // cycles across the plot
+    let max_frequency = (plot.wave.len() / 2).max(1) as f32;
+    let mut filter_row = row![
+        text(format!("fc: {:.1}", filter.cutoff)),
+        slider(0.5..=max_frequency, filter.cutoff, Message::SetCutoff).step(0.5)
+    ].spacing(10).align_y(Alignment::Center);
+    if filter.kind.is_band()
+    {
+        filter_row = filter_row.push(text(format!("Width: {:.1}", filter.width)))
+            .push(slider(0.5..=max_frequency, filter.width, Message::SetFilterWidth).step(0.5));
+    }
+    
+    let table_el: Element<Message> = match state.view_table
+    {
+        true => harmonic_table(state),
+        false => Space::new(Length::Fixed(0.0), Length::Fixed(0.0)).into()
+    };
+    
+    let screen_el: Element<Message> = match &state.fringes
+    {
+        Some(report) => row![
+            column![
+                screen::<_, 0>(&state.colours, state.exposure),
+                screen::<_, 1>(&report.markers, 1.0)
+                    .height(Length::Fixed(8.0))
+            ].spacing(2).height(Length::Fill),
+            fringe_panel(report)
+        ].spacing(10).height(Length::Fill).into(),
+        None => screen::<_, 0>(&state.colours, state.exposure).into()
+    };
+    
     let spec_scale = state.plot.get_scale();
+    let spec_axis = state.spec_axis();
+    let harmonic = matches!(state.plot.get_mapping(), WaveMapping::Harmonic(_));
+    let band = state.plot.get_band();
+    let false_colour = *state.plot.get_colour_map() == ColourMap::FalseColour;
+    let generator = &state.generator;
+    let mut generator_row = row![
+        text(format!("φ: {:.2}", generator.phase)),
+        slider(0.0..=1.0, generator.phase, Message::SetGenPhase).step(0.01)
+    ].spacing(10).align_y(Alignment::Center);
+    if let Some(param) = generator.shape.param()
+    {
+        generator_row = generator_row.push(text(format!("{}: {:.2}", param.name, generator.param)))
+            .push(slider(param.range, generator.param, Message::SetGenParam).step(param.step));
+    }
+    let mut audio = column![
+        row![
+            button("Load WAV").on_press(Message::LoadWav),
+            button("Save WAV").on_press(Message::SaveWav),
+            text(format!("{:.0}Hz", state.loop_pitch)),
+            slider(55.0..=1760.0, state.loop_pitch, Message::SetLoopPitch).step(1.0)
+        ].spacing(10).align_y(Alignment::Center)
+    ].spacing(5);
+    if !state.audio.is_empty()
+    {
+        let a = &state.audio;
+        audio = audio.push(row![
+            text(format!("Start: {}", a.start)),
+            slider(0.0..=(a.max_start() as f32), a.start as f32, Message::SetAudioStart)
+                .step(1.0).on_release(Message::EndAudioEdit)
+        ].spacing(10).align_y(Alignment::Center)).push(row![
+            text(format!("Period: {} ({:.1}Hz)", a.period, a.frequency())),
+            slider(2.0..=(a.max_period() as f32), a.period as f32, Message::SetAudioPeriod)
+                .step(1.0).on_release(Message::EndAudioEdit),
+            button("Detect").on_press(Message::DetectPeriod)
+        ].spacing(10).align_y(Alignment::Center));
+    }
+    let mut formula = column![
+        row![
+            text_input("sin(2*pi*t) or exp(-((l-550)/20)^2)", &state.formula)
+                .on_input(Message::SetFormula)
+                .on_submit(Message::FormulaWave),
+            button("Wave").on_press(Message::FormulaWave),
+            button("Spectrum").on_press(Message::FormulaSpectrum)
+        ].spacing(10).align_y(Alignment::Center)
+    ].spacing(2);
+    if let Some(e) = &state.formula_error
+    {
+        formula = formula.push(text(e).color(Color::from_rgb(1.0, 0.35, 0.35)));
+    }
+    let windows = [Window::Rectangular, Window::Hann, Window::Hamming, Window::Blackman,
+        Window::Kaiser(state.kaiser_beta)];
     let view = row![
         column![
-            screen(&state.colours, state.exposure),
+            screen_el,
             row![
                 text(format!("Exposure: {:.3}", state.exposure)),
                 slider(0.1..=10.0, state.exposure, Message::SetExpo).step(0.001)
-                    .width(Length::Fill)
+                    .width(Length::Fill),
+                toggler(state.scene.get_solver() == Solver::AngularSpectrum)
+                    .label("FFT")
+                    .on_toggle(Message::FastSolver)
             ].spacing(10).width(Length::Fixed(SCREEN_SIZE as f32))
                 .align_y(Alignment::Center)
                 .padding(Padding::new(5.0)),
+            row![
+                text("File"),
+                text_input("Default for each button", &state.file_path).on_input(Message::SetFilePath)
+            ].spacing(10).width(Length::Fixed(SCREEN_SIZE as f32))
+                .align_y(Alignment::Center),
+            row![
+                button("Export CSV").on_press(Message::ExportProfile),
+                button("Fit").on_press(Message::FitMeasured),
+                toggler(state.fringes.is_some())
+                    .label("Analyse")
+                    .on_toggle(Message::Analyse),
+                toggler(state.export_per_wave)
+                    .label("Per λ")
+                    .on_toggle(Message::ExportPerWave),
+                text(&state.status)
+            ].spacing(10).width(Length::Fixed(SCREEN_SIZE as f32))
+                .align_y(Alignment::Center),
+            plotter::<_, _, _, _, _, 3>(Some(Message::TargetSize), Message::PlotTarget, Message::DragTarget,
+                &state.target, 0.0..1.0, AxisScale::Linear, Vector4::new(1.0, 1.0, 1.0, 1.0))
+                .width(Length::Fixed(SPECTRUM_SIZE as f32))
+                .height(Length::Fixed(80.0)),
+            row![
+                button("Target from screen").on_press(Message::TargetFromScreen),
+                toggler(state.optimise_phases)
+                    .label("Phases")
+                    .on_toggle(Message::OptimisePhases),
+                match state.optimising
+                {
+                    Some(_) => button("Cancel").on_press(Message::CancelOptimise),
+                    None => button("Optimise").on_press(Message::StartOptimise)
+                }
+            ].spacing(10).align_y(Alignment::Center),
             container(scene(&state.scene_ui.lines, &state.scene, state.scene_ui.zoom, state.scene_ui.pan, &SCENE_MESSAGES)
                 .width(Length::Fill).height(Length::Fill)).center(Length::Fill)
                 .style(|_| Style::default().background(Background::Color(Color::from_rgb(0.15, 0.15, 0.15))))  
@@ -421,25 +1580,40 @@ fn view(state: &State) -> Element<Message>
             .padding(Padding::new(5.0)),
         column![
             row![
-                button("Sine").on_press(Message::FillSine),
-                button("Triangle").on_press(Message::FillTriangle),
-                button("Saw").on_press(Message::FillSaw),
-                button("Square").on_press(Message::FillSquare),
+                pick_list(Shape::ALL, Some(state.generator.shape), Message::SetShape),
+                button("Fill").on_press(Message::Generate),
                 button("Clear").on_press(Message::Clear),
+                button("Undo").on_press_maybe(state.history.can_undo().then_some(Message::Undo)),
+                button("Redo").on_press_maybe(state.history.can_redo().then_some(Message::Redo)),
                 toggler(state.view_phase)
                     .label("Phase")
-                    .on_toggle(Message::ViewPhase)
+                    .on_toggle(Message::ViewPhase),
+                pick_list(PhaseMode::all(state.phase_seed), Some(state.plot.get_phase_mode()), Message::SetPhaseMode),
+                text_input("Seed", &state.phase_seed_text).on_input(Message::SetPhaseSeed)
+                    .on_submit(Message::ApplyPhaseSeed).width(Length::Fixed(80.0)),
+                toggler(state.view_table)
+                    .label("Table")
+                    .on_toggle(Message::ViewTable)
             ].spacing(10)
                 .align_y(Alignment::Center)
                 .padding(Padding::new(5.0)),
+            row![
+                text(format!("A: {:.2}", generator.amplitude)),
+                slider(0.0..=1.0, generator.amplitude, Message::SetGenAmplitude).step(0.01),
+                text(format!("f: {:.0}", generator.frequency)),
+                slider(1.0..=32.0, generator.frequency, Message::SetGenFrequency).step(1.0)
+            ].spacing(10).align_y(Alignment::Center),
+            generator_row,
+            formula,
+            audio,
                 
             plotter::<_, _, _, _, _, 0>(Some(Message::PlotSize), Message::PlotWave, Message::DragWave,
-                &plot.wave, -1.0..1.0, Vector4::new(1.0, 0.0, 0.0, 1.0))
+                &plot.wave, -1.0..1.0, AxisScale::Linear, Vector4::new(1.0, 0.0, 0.0, 1.0))
                 .width(Length::Fixed(PLOTTER_SIZE as f32)),
             
             row![
-                plotter::<fn(usize) -> Message, _, _, _, _, 1>(None, Message::PlotFreq, Message::DragFreq,
-                    &plot.spectrum, 0.0..1.0, Vector4::zero())
+                shader(Plot::<fn(usize) -> Message, _, _, _, _, 1>::new(None, Message::PlotFreq, Message::DragFreq,
+                    &plot.spectrum, 0.0..1.0, spec_axis, Vector4::zero()).on_release(Message::EndDragFreq))
                     .width(Length::Fixed(SPECTRUM_SIZE as f32)),
                 
                 column![
@@ -450,9 +1624,83 @@ fn view(state: &State) -> Element<Message>
             ].spacing(10).height(Length::Shrink)
                 .align_y(Alignment::Center)
                 .padding(Padding::new(5.0)),
+            row![
+                toggler(state.view_decibels)
+                    .label("dB")
+                    .on_toggle(Message::ViewDecibels),
+                text(format!("Floor: {:.0} dB", state.decibel_floor)),
+                slider(-120.0..=-20.0, state.decibel_floor, Message::SetDecibelFloor).step(1.0)
+            ].spacing(10).align_y(Alignment::Center),
             
             phase_el,
-            horizontal_rule(2)
+            table_el,
+            row![
+                pick_list(windows, Some(state.plot.get_window()), Message::SetWindow),
+                pick_list(PADDINGS, Some(state.plot.get_padding()), Message::SetPadding),
+                text(format!("β: {:.1}", state.kaiser_beta)),
+                slider(0.0..=20.0, state.kaiser_beta, Message::SetKaiserBeta).step(0.1)
+            ].spacing(10).align_y(Alignment::Center),
+            row![
+                pick_list(FilterKind::ALL, Some(filter.kind), Message::SetFilterKind),
+                button("Filter").on_press(Message::ApplyFilter),
+                text(format!("Order: {}", filter.order)),
+                slider(1.0..=8.0, filter.order as f32, Message::SetFilterOrder).step(1.0)
+            ].spacing(10).align_y(Alignment::Center),
+            filter_row,
+            row![
+                button("Chirp").on_press(Message::FillChirp),
+                button("Load signal").on_press(Message::LoadSignal)
+            ].spacing(10).align_y(Alignment::Center),
+            plotter::<_, _, _, _, _, 4>(Some(Message::SignalSize), Message::PlotSignal, Message::DragSignal,
+                &state.signal.view, -1.0..1.0, AxisScale::Linear, Vector4::new(1.0, 0.5, 0.0, 1.0))
+                .width(Length::Fixed(SPECTRUM_SIZE as f32))
+                .height(Length::Fixed(60.0)),
+            spectrogram::<_, _, 0>(&state.signal.image, state.signal.size, state.signal.slice, Message::PickSlice)
+                .width(Length::Fixed(SPECTRUM_SIZE as f32))
+                .height(Length::Fixed(120.0)),
+            horizontal_rule(2),
+            
+            row![
+                button("Blackbody").on_press(Message::FillSource(SourceSpectrum::Blackbody(state.temperature as f64))),
+                button("Laser").on_press(Message::FillSource(SourceSpectrum::Laser(vec![state.laser_line as f64]))),
+                button("Add Line").on_press(Message::AddLaserLine),
+                button("Hg").on_press(Message::FillSource(SourceSpectrum::Mercury)),
+                button("Na").on_press(Message::FillSource(SourceSpectrum::Sodium)),
+                button("H").on_press(Message::FillSource(SourceSpectrum::Hydrogen))
+            ].spacing(10)
+                .align_y(Alignment::Center),
+            row![
+                text(format!("T: {:.0}K", state.temperature)),
+                slider(1000.0..=12000.0, state.temperature, Message::SetTemperature).step(10.0)
+                    .on_release(Message::EndSourceEdit)
+            ].spacing(10).align_y(Alignment::Center),
+            row![
+                text(format!("Line: {:.1}nm", state.laser_line)),
+                slider((band.0 as f32)..=(band.1 as f32), state.laser_line, Message::SetLaserLine).step(0.1)
+                    .on_release(Message::EndSourceEdit)
+            ].spacing(10).align_y(Alignment::Center),
+            row![
+                text(format!("Width: {:.1}nm", state.line_width)),
+                slider(0.5..=20.0, state.line_width, Message::SetLineWidth).step(0.1)
+                    .on_release(Message::EndSourceEdit)
+            ].spacing(10).align_y(Alignment::Center),
+            horizontal_rule(2),
+            
+            row![
+                toggler(harmonic)
+                    .label("Harmonic")
+                    .on_toggle(Message::HarmonicMap),
+                text(format!("λ0: {:.0}nm", state.fundamental)),
+                slider(400.0..=20000.0, state.fundamental, Message::SetFundamental).step(1.0)
+            ].spacing(10).align_y(Alignment::Center),
+            row![
+                text(format!("Band: {:.0}-{:.0}nm", band.0, band.1)),
+                slider(100.0..=1500.0, band.0 as f32, Message::SetBandMin).step(1.0),
+                slider(200.0..=3000.0, band.1 as f32, Message::SetBandMax).step(1.0),
+                toggler(false_colour)
+                    .label("False colour")
+                    .on_toggle(Message::FalseColour)
+            ].spacing(10).align_y(Alignment::Center)
         ].spacing(10).width(Length::Shrink)
             .align_x(Alignment::Center)
             .padding(Padding::new(5.0))
@@ -463,8 +1711,23 @@ fn view(state: &State) -> Element<Message>
     return view.into();
 }
 
+fn subscription(_state: &State) -> Subscription<Message>
+{
+    // ctrl z and ctrl shift z
+    return keyboard::on_key_press(|key, mods| match key.as_ref()
+    {
+        Key::Character(c) if mods.command() && c.eq_ignore_ascii_case("z") => match mods.shift()
+        {
+            true => Some(Message::Redo),
+            false => Some(Message::Undo)
+        },
+        _ => None
+    });
+}
+
 fn main() {
     let _ = iced::application("Plotter", update, view)
+        .subscription(subscription)
         .theme(|_| iced::Theme::Dark)
         .run();
 }
\ No newline at end of file
@@ -0,0 +1,93 @@
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use backend::nelder_mead;
+
+use crate::scene::{Scene, SceneSlit};
+
+#[derive(Debug, Clone)]
+pub enum OptimiseEvent
+{
+    Step(usize, f64),
+    Done(usize, Vec<SceneSlit>)
+}
+
+/// parameters per slit, position width and optionally phase
+fn stride(phases: bool) -> usize
+{
+    return if phases { 3 } else { 2 };
+}
+
+fn to_slits(params: &[f64], phases: bool) -> Vec<SceneSlit>
+{
+    return params.chunks(stride(phases)).map(|p| SceneSlit {
+        position: p[0],
+        width: p[1],
+        phase: if phases { p[2] } else { 0.0 }
+    }).collect();
+}
+
+/// slits must be inside the wall and not overlap
+fn valid(slits: &[SceneSlit], len: f64) -> bool
+{
+    let mut last = 0.0;
+    for s in slits
+    {
+        if s.width <= 0.0 || s.get_left() < last { return false; }
+        last = s.get_right();
+    }
+    return last <= len;
+}
+
+/// mismatch between the normalised screen intensity and `target`
+fn cost(scene: &mut Scene, wall: usize, target: &[f64], params: &[f64], phases: bool) -> f64
+{
+    let slits = to_slits(params, phases);
+    if !valid(&slits, scene.get_wall(wall).len()) { return f64::INFINITY; }
+    scene.set_slits(wall, slits);
+    
+    let profile = scene.generate_profile(target.len(), false);
+    // least squares scale
+    let (my, mm) = profile.total.iter().zip(target)
+        .fold((0.0, 0.0), |(my, mm), (m, y)| (my + (m * y), mm + (m * m)));
+    let s = if mm > 0.0 { my / mm } else { 0.0 };
+    
+    let norm = target.iter().fold(0.0, |a, t| a + (t * t)).max(f64::EPSILON);
+    return profile.total.iter().zip(target)
+        .fold(0.0, |a, (m, y)| a + (y - (s * m)).powi(2)) / norm;
+}
+
+/// adjusts the slits of `wall` so the screen intensity approaches `target`,
+/// runs until converged, `max_iter` or `cancel` is set
+pub fn optimise<F>(mut scene: Scene, wall: usize, target: &[f64], phases: bool, max_iter: usize,
+    cancel: Arc<AtomicBool>, mut progress: F) -> Vec<SceneSlit>
+    where F: FnMut(usize, f64)
+{
+    let start_slits = scene.get_wall(wall).get_slits().to_vec();
+    if start_slits.len() == 0 || target.len() < 2 { return start_slits; }
+    
+    let mut start = Vec::with_capacity(start_slits.len() * stride(phases));
+    let mut step = Vec::with_capacity(start.capacity());
+    for s in &start_slits
+    {
+        start.push(s.position);
+        step.push(s.width * 2.0);
+        start.push(s.width);
+        step.push(s.width * 0.5);
+        if phases
+        {
+            start.push(s.phase);
+            step.push(PI * 0.5);
+        }
+    }
+    
+    let (best, _) = nelder_mead(|p| cost(&mut scene, wall, target, p, phases), &start, &step,
+        max_iter, 1e-9, |i, c|
+        {
+            progress(i, c);
+            return !cancel.load(Ordering::Relaxed);
+        });
+    
+    return to_slits(&best, phases);
+}
\ No newline at end of file
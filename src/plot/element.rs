@@ -11,29 +11,56 @@ use zene_structs::Vector4;
 
 use super::renderer::{PlotRender, TextureData};
 
+/// how values are placed vertically
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AxisScale
+{
+    #[default]
+    Linear,
+    /// decibels relative to 1, for a 0 to 1 range.
+    /// the bottom of the plot is at the given floor, which is negative
+    Decibel(f32)
+}
+
+impl AxisScale
+{
+    /// height in the data range of `v`
+    pub fn height(&self, v: f32) -> f32
+    {
+        return match self
+        {
+            AxisScale::Linear => v,
+            AxisScale::Decibel(floor) =>
+            {
+                if v <= 0.0 { return 0.0; }
+                (1.0 - (20.0 * v.log10() / floor.min(-f32::EPSILON))).max(0.0)
+            }
+        };
+    }
+    /// value at height `h` in the data range, the bottom of a decibel plot is zero
+    pub fn value(&self, h: f32) -> f32
+    {
+        return match self
+        {
+            AxisScale::Linear => h,
+            AxisScale::Decibel(floor) =>
+            {
+                if h <= 0.0 { return 0.0; }
+                10.0f32.powf(floor * (1.0 - h) / 20.0)
+            }
+        };
+    }
+}
+
 pub fn plotter<'a, S, F, G, D: TextureData, Message, const ID: usize>(on_size: Option<S>, on_place: F,
-    on_drag: G, data: &'a [D], data_range: Range<f32>, colour: Vector4) -> Shader<Message, Plot<'a, S, F, G, D, Message, ID>>
+    on_drag: G, data: &'a [D], data_range: Range<f32>, axis: AxisScale, colour: Vector4) -> Shader<Message, Plot<'a, S, F, G, D, Message, ID>>
     where S: Fn(usize) -> Message,
         F: Fn(usize, f32) -> Message,
         G: Fn(usize, f32) -> Message,
-        D: Debug + Send + Sync + NoUninit + 'static
+        D: Debug + Send + Sync + NoUninit + 'static,
+        Message: Clone
 {
-    let s = 1.0 / (data_range.end - data_range.start);
-    let off = -data_range.start * s;
-    let top = (data_range.end * s) + off;
-    
-    return shader(
-        Plot {
-            colour,
-            on_size,
-            on_place,
-            on_drag,
-            data,
-            scale: s,
-            uv_scale: top,
-            uv_offset: off
-        }
-    );
+    return shader(Plot::new(on_size, on_place, on_drag, data, data_range, axis, colour));
 }
 
 pub struct Plot<'a, S, F, G, D: TextureData, Message, const ID: usize>
@@ -45,17 +72,54 @@ pub struct Plot<'a, S, F, G, D: TextureData, Message, const ID: usize>
     on_size: Option<S>,
     on_place: F,
     on_drag: G,
+    /// sent when a press or drag ends
+    on_release: Option<Message>,
     data: &'a [D],
+    axis: AxisScale,
     scale: f32,
     uv_scale: f32,
     uv_offset: f32
 }
 
+impl<'a, S, F, G, D: TextureData, Message, const ID: usize> Plot<'a, S, F, G, D, Message, ID>
+    where S: Fn(usize) -> Message,
+        F: Fn(usize, f32) -> Message,
+        G: Fn(usize, f32) -> Message
+{
+    pub fn new(on_size: Option<S>, on_place: F, on_drag: G, data: &'a [D], data_range: Range<f32>,
+        axis: AxisScale, colour: Vector4) -> Self
+    {
+        let s = 1.0 / (data_range.end - data_range.start);
+        let off = -data_range.start * s;
+        let top = (data_range.end * s) + off;
+        
+        return Self {
+            colour,
+            on_size,
+            on_place,
+            on_drag,
+            on_release: None,
+            data,
+            axis,
+            scale: s,
+            uv_scale: top,
+            uv_offset: off
+        };
+    }
+    /// `message` is sent when a press or drag ends
+    pub fn on_release(mut self, message: Message) -> Self
+    {
+        self.on_release = Some(message);
+        return self;
+    }
+}
+
 impl<'a, S, F, G, D: TextureData, Message, const ID: usize> Program<Message> for Plot<'a, S, F, G, D, Message, ID>
     where S: Fn(usize) -> Message,
         F: Fn(usize, f32) -> Message,
         G: Fn(usize, f32) -> Message,
-        D: Debug + Send + Sync + NoUninit + 'static
+        D: Debug + Send + Sync + NoUninit + 'static,
+        Message: Clone
 {
     type State = bool;
     type Primitive = PlotRender<D, ID>;
@@ -66,8 +130,13 @@ impl<'a, S, F, G, D: TextureData, Message, const ID: usize> Program<Message> for
         _cursor: iced::advanced::mouse::Cursor,
         _bounds: Rectangle) -> Self::Primitive
     {
+        let data = match self.axis
+        {
+            AxisScale::Linear => self.data.to_vec(),
+            axis => self.data.iter().map(|d| d.map_value(|v| axis.height(v))).collect()
+        };
         return PlotRender::new(
-            self.data.to_vec(), self.colour,
+            data, self.colour,
             Vector4::new(0.0, 0.0, 0.0, 1.0), self.scale, self.uv_scale, self.uv_offset);
     }
     
@@ -96,7 +165,7 @@ impl<'a, S, F, G, D: TextureData, Message, const ID: usize> Program<Message> for
                 {
                     *state = true;
                     let p = cursor_position - bounds.position();
-                    let v = ((hh - p.y) * self.uv_scale / hh - self.uv_offset) / self.scale;
+                    let v = self.axis.value(((hh - p.y) * self.uv_scale / hh - self.uv_offset) / self.scale);
                     let x = p.x as usize;
                     
                     return (Status::Captured, Some((self.on_place)(x, v)));
@@ -107,7 +176,7 @@ impl<'a, S, F, G, D: TextureData, Message, const ID: usize> Program<Message> for
                 if *state
                 {
                     *state = false;
-                    return (Status::Captured, None);
+                    return (Status::Captured, self.on_release.clone());
                 }
             },
             Event::Mouse(iced::mouse::Event::CursorMoved { position }) =>
@@ -116,7 +185,7 @@ impl<'a, S, F, G, D: TextureData, Message, const ID: usize> Program<Message> for
                 {
                     let p = position - bounds.position();
                     let p = Point::new(p.x.clamp(0.0, bounds.width - 1.0), p.y.clamp(0.0, bounds.height));
-                    let v = ((hh - p.y) * self.uv_scale / hh - self.uv_offset) / self.scale;
+                    let v = self.axis.value(((hh - p.y) * self.uv_scale / hh - self.uv_offset) / self.scale);
                     let x = p.x as usize;
                     
                     return (Status::Captured, Some((self.on_drag)(x, v)));
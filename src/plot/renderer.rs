@@ -10,17 +10,30 @@ use zene_structs::{Vector2, Vector4};
 
 use crate::SPECTRUM_SIZE;
 
-pub trait TextureData
+pub trait TextureData: Copy
 {
     const FORMAT: TextureFormat;
+    
+    /// applies `f` to the plotted value, the first channel
+    fn map_value<M: Fn(f32) -> f32>(self, f: M) -> Self;
 }
 impl TextureData for f32
 {
     const FORMAT: TextureFormat = TextureFormat::R32Float;
+    
+    fn map_value<M: Fn(f32) -> f32>(self, f: M) -> Self
+    {
+        return f(self);
+    }
 }
 impl TextureData for [f32; 4]
 {
     const FORMAT: TextureFormat = TextureFormat::Rgba32Float;
+    
+    fn map_value<M: Fn(f32) -> f32>(self, f: M) -> Self
+    {
+        return [f(self[0]), self[1], self[2], self[3]];
+    }
 }
 
 #[derive(Debug)]
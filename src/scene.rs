@@ -1,19 +1,27 @@
 pub mod element;
 pub mod renderer;
 pub mod ui_manager;
+pub mod fringes;
+pub mod propagate;
+pub mod ops;
 
 use core::f64;
+use std::io::{self, Write};
+use std::sync::Arc;
 
-use backend::{Colour, EMEnv, Slit, Wave};
-use zene_structs::{Vector2, Vector3, Vector};
+use backend::{fit_slits, Colour, EMEnv, FftPlan, ScreenProfile, Slit, SlitFit, Wave};
+use zene_structs::{Line2, Vector2, Vector3, Vector};
 
-use crate::wave_data::WaveData;
+use crate::wave_data::{WaveData, DEFAULT_BAND};
 
-#[derive(Debug, Clone, Default, Copy)]
+use propagate::Solver;
+
+#[derive(Debug, Clone, Default, Copy, PartialEq)]
 pub struct SceneSlit
 {
     pub width: f64,
-    pub position: f64
+    pub position: f64,
+    pub phase: f64
 }
 
 impl SceneSlit
@@ -24,7 +32,8 @@ impl SceneSlit
     }
     pub fn get_slit<'a>(&self, wall: &Wall, waves: &'a [Wave<f64>]) -> Slit<'a, f64>
     {
-        return Slit::new(self.width, self.get_position(&wall), wall.dir.rotated_90(), waves);
+        return Slit::new(self.width, self.get_position(&wall), wall.dir.rotated_90(), waves)
+            .with_phase(self.phase);
     }
     pub fn get_left(&self) -> f64
     {
@@ -103,6 +112,10 @@ impl Wall
     {
         return self.a.distance(self.b);
     }
+    pub fn get_slits(&self) -> &[SceneSlit]
+    {
+        return &self.slits;
+    }
     // pub fn shift(&mut self, off: Vector2<f64>)
     // {
     //     self.a += off;
@@ -127,10 +140,9 @@ impl Wall
         };
     }
     
-    pub fn insert_slit(&mut self, width: f64, position: f64) -> usize
+    pub fn insert_slit(&mut self, slit: SceneSlit) -> usize
     {
-        let index = self.slits.iter().position(|s| s.position > position);
-        let slit = SceneSlit { width, position };
+        let index = self.slits.iter().position(|s| s.position > slit.position);
         match index
         {
             Some(i) =>
@@ -154,32 +166,78 @@ pub struct Scene
 {
     pub env: EMEnv<f64>,
     waves: Box<[Wave<f64>]>,
-    walls: Vec<Wall>
+    walls: Vec<Wall>,
+    /// lengths relative to `DEFAULT_BAND`
+    band_scale: f64,
+    solver: Solver,
+    /// kept for the angular spectrum solver
+    plan: Option<Arc<FftPlan<f64>>>
 }
 
+/// slit width for `DEFAULT_BAND`, lengths are in nm
 pub const DEFAULT_WIDTH: f64 = 1560.0;
 
+fn band_scale(band: (f64, f64)) -> f64
+{
+    // keep the scene the same size relative to the wavelengths
+    return (band.0 + band.1) / (DEFAULT_BAND.0 + DEFAULT_BAND.1);
+}
+
 impl Default for Scene
 {
     fn default() -> Self
     {
+        return Self::new(DEFAULT_BAND);
+    }
+}
+
+impl Scene
+{
+    pub fn new(band: (f64, f64)) -> Self
+    {
+        let s = band_scale(band);
         return Self {
             env: EMEnv::new(
-                Vector2::new(-2e9, 1e9),
-                Vector2::new(2e9, 1e9)),
+                Vector2::new(-2e9 * s, 1e9 * s),
+                Vector2::new(2e9 * s, 1e9 * s)),
             waves: Default::default(),
             walls: vec![Wall {
-                a: Vector2::new(-1e9, -1e9),
-                b: Vector2::new(1e9, -1e9),
+                a: Vector2::new(-1e9 * s, -1e9 * s),
+                b: Vector2::new(1e9 * s, -1e9 * s),
                 dir: Vector2::new(1.0, 0.0),
-                slits: vec![SceneSlit { width: DEFAULT_WIDTH, position: 1e9}]
-            }]
+                slits: vec![SceneSlit { width: DEFAULT_WIDTH * s, position: 1e9 * s, phase: 0.0 }]
+            }],
+            band_scale: s,
+            solver: Solver::Direct,
+            plan: None
         };
     }
-}
-
-impl Scene
-{
+    /// width given to new slits
+    pub fn default_width(&self) -> f64
+    {
+        return DEFAULT_WIDTH * self.band_scale;
+    }
+    /// scales every length with the band, returns the factor
+    pub fn set_band(&mut self, band: (f64, f64)) -> f64
+    {
+        let s = band_scale(band);
+        let r = s / self.band_scale;
+        self.band_scale = s;
+        
+        self.env.screen = (self.env.screen.0 * r, self.env.screen.1 * r);
+        for w in &mut self.walls
+        {
+            w.a = w.a * r;
+            w.b = w.b * r;
+            for slit in &mut w.slits
+            {
+                slit.width *= r;
+                slit.position *= r;
+            }
+        }
+        return r;
+    }
+    
     pub fn compute_waves(&mut self, wd: &WaveData)
     {
         let scale = wd.get_scale();
@@ -194,9 +252,11 @@ impl Scene
         
         self.waves = waves.collect::<Box<[Wave<f64>]>>();
     }
-    pub fn simulate<S>(&self, wave_map: &[(f64, Vector3)], samples: &mut [S])
+    pub fn simulate<S>(&mut self, wave_map: &[(f64, Vector3)], samples: &mut [S])
         where S: From<Vector3>
     {
+        if self.solver == Solver::AngularSpectrum && self.propagate_pattern(wave_map, samples) { return; }
+        
         let sim_slits = self.get_slits();
         self.env.generate_pattern(&sim_slits, wave_map, samples);
     }
@@ -234,23 +294,74 @@ impl Scene
         }
         return sim_slits;
     }
-    pub fn get_wall(&self, i: usize) -> &Wall
+    pub fn generate_profile(&self, count: usize, per_wave: bool) -> ScreenProfile<f64>
     {
-        return &self.walls[i];
+        let sim_slits = self.get_slits();
+        let wavelengths: Vec<f64> = self.waves.iter().map(|w| w.lambda).collect();
+        return self.env.generate_profile(&sim_slits, &wavelengths, count, per_wave);
     }
-    
-    pub fn insert_slit(&mut self, slit: (SceneSlit, usize))
+    /// angle in degrees of each point from the normal of each wall
+    pub fn wall_angles(&self, points: &[Vector2<f64>]) -> Vec<Vec<f64>>
+    {
+        return self.walls.iter().map(|w|
+        {
+            let centre = (w.a + w.b) * 0.5;
+            let n = w.dir.rotated_90();
+            return points.iter().map(|p|
+            {
+                let diff = *p - centre;
+                return diff.perp_dot(n).atan2(diff.dot(n)).to_degrees();
+            }).collect::<Vec<f64>>();
+        }).collect();
+    }
+    pub fn write_profile_csv<W: Write>(&self, out: &mut W, count: usize, per_wave: bool) -> io::Result<()>
+    {
+        let profile = self.generate_profile(count, per_wave);
+        let angles: Vec<(String, Vec<f64>)> = self.wall_angles(&profile.points).into_iter()
+            .enumerate().map(|(i, a)| (format!("angle_wall_{i}"), a)).collect();
+        
+        return profile.write_csv(out, &angles);
+    }
+    /// distance from the centre of `wall` to the screen
+    pub fn screen_distance(&self, wall: usize) -> f64
+    {
+        let w = &self.walls[wall];
+        let dir = (self.env.screen.1 - self.env.screen.0).normalised();
+        return Line2::new(dir, self.env.screen.0).squared_distance_from_point((w.a + w.b) * 0.5).sqrt();
+    }
+    /// `measured` is (position along the screen, intensity) in scene units
+    pub fn fit_measured(&self, wall: usize, measured: &[(f64, f64)], max_count: usize) -> Option<SlitFit<f64>>
     {
-        self.walls[slit.1].insert_slit(slit.0.width, slit.0.position);
+        return fit_slits(measured, self.screen_distance(wall), &self.waves, max_count);
     }
-    pub fn delete_slit(&mut self, wall: usize, slit: usize)
+    /// replaces the slits of `wall` with the fitted ones, and places the screen parallel at `distance`,
+    /// centred on the wall. slits are kept on the wall
+    pub fn apply_fit(&mut self, wall: usize, fit: &SlitFit<f64>, distance: f64)
     {
-        self.walls[wall].slits.remove(slit);
+        let length = self.env.screen.0.distance(self.env.screen.1);
+        let w = &mut self.walls[wall];
+        let len = w.len();
+        // the fitted axis relative to the middle of the screen
+        let centre = (len * 0.5) + fit.offset - (length * 0.5);
+        let mid = (fit.count - 1) as f64 * 0.5;
+        let (lo, hi) = (fit.width * 0.5, len - (fit.width * 0.5));
+        w.slits = (0..fit.count).map(|j| SceneSlit {
+            width: fit.width,
+            position: (centre + ((j as f64 - mid) * fit.separation)).clamp(lo, hi.max(lo)),
+            phase: 0.0
+        }).collect();
+        
+        let c = (w.a + w.b) * 0.5 + (w.dir.rotated_90() * distance);
+        let off = w.dir * (length * 0.5);
+        self.env.screen = (c - off, c + off);
+    }
+    pub fn get_wall(&self, i: usize) -> &Wall
+    {
+        return &self.walls[i];
     }
-    pub fn delete_wall(&mut self, wall: usize)
+    pub fn set_slits(&mut self, wall: usize, slits: Vec<SceneSlit>)
     {
-        if (self.walls.len() <= 1) { return; }
-        self.walls.remove(wall);
+        self.walls[wall].slits = slits;
     }
 }
 
@@ -309,7 +420,7 @@ impl SceneUIData
     {
         let mut data = Vec::<LineData>::with_capacity(scene.walls.len() * 4);
         
-        let ghost = self.ghost.unwrap_or((SceneSlit { width: f64::NAN, position: f64::NAN }, 0));
+        let ghost = self.ghost.unwrap_or((SceneSlit { width: f64::NAN, position: f64::NAN, phase: 0.0 }, 0));
         
         for (i, w) in scene.walls.iter().enumerate()
         {
@@ -18,6 +18,7 @@ pub struct MessageFuncs<Message>
     pub on_select: fn(SceneUIRef) -> Message,
     pub on_delete: fn(SceneUIRef) -> Message,
     pub on_drag: fn(SceneUIRef, Vector2<f64>, Vector2<f64>, Modifiers) -> Message,
+    pub on_drag_end: fn() -> Message,
     pub on_cancel: fn() -> Message,
     pub on_ghost: fn(usize, f64) -> Message,
     pub on_ghost_end: fn(bool) -> Message,
@@ -211,7 +212,7 @@ impl<'a, Message: 'static> Program<Message> for SceneEl<'a, Message>
                 {
                     state.press_select = false;
                     self.mouse_hover(state, bounds, pan_div, cursor, shell, true);
-                    return (Status::Captured, None);
+                    return (Status::Captured, Some((self.funcs.on_drag_end)()));
                 }
             },
             Event::Mouse(iced::mouse::Event::CursorMoved { position }) =>
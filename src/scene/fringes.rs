@@ -0,0 +1,115 @@
+use backend::{analyse_fringes, Colour, FringeAnalysis, SlitTheory};
+use zene_structs::{Line2, Vector2};
+
+use super::Scene;
+
+/// maxima dimmer than this fraction of the brightest are ignored
+const THRESHOLD: f64 = 0.01;
+
+const MEASURED_MAX: Colour = Colour::rgb(1.0, 1.0, 1.0);
+const MEASURED_MIN: Colour = Colour::rgb(0.35, 0.35, 0.35);
+const THEORY_MAX: Colour = Colour::rgb(0.0, 1.0, 0.2);
+const THEORY_MIN: Colour = Colour::rgb(1.0, 0.1, 0.0);
+
+#[derive(Debug, Clone, Default)]
+pub struct FringeReport
+{
+    pub analysis: FringeAnalysis<f64>,
+    pub theory: Option<SlitTheory<f64>>,
+    /// distances along the screen of the predicted double slit maxima
+    pub theory_maxima: Vec<f64>,
+    /// distances along the screen of the predicted single slit minima
+    pub theory_minima: Vec<f64>,
+    pub markers: Box<[Colour]>
+}
+
+impl Scene
+{
+    /// theory for the first two slits of the first wall that has any,
+    /// along with the centre of the slits and the wall index
+    pub fn slit_theory(&self) -> Option<(SlitTheory<f64>, Vector2<f64>, usize)>
+    {
+        let (i, w) = self.walls.iter().enumerate().find(|(_, w)| w.slits.len() != 0)?;
+        let wave = self.waves.iter().max_by(|a, b| a.amplitude.partial_cmp(&b.amplitude).unwrap())?;
+        
+        let a = &w.slits[0];
+        let (separation, centre) = match w.slits.get(1)
+        {
+            Some(b) => (b.position - a.position, (a.get_position(w) + b.get_position(w)) * 0.5),
+            None => (0.0, a.get_position(w))
+        };
+        
+        let screen_dir = (self.env.screen.1 - self.env.screen.0).normalised();
+        let distance = Line2::new(screen_dir, self.env.screen.0).squared_distance_from_point(centre).sqrt();
+        
+        return Some((SlitTheory {
+            separation,
+            width: a.width,
+            distance,
+            wavelength: wave.lambda
+        }, centre, i));
+    }
+    
+    /// distance along the screen where a ray from `origin` meets it
+    fn screen_position(&self, origin: Vector2<f64>, dir: Vector2<f64>) -> Option<f64>
+    {
+        let s0 = self.env.screen.0;
+        let e = self.env.screen.1 - s0;
+        let cross = e.perp_dot(dir);
+        if cross == 0.0 { return None; }
+        
+        let u = (origin - s0).perp_dot(dir) / cross;
+        let v = (origin - s0).perp_dot(e) / cross;
+        if v < 0.0 || u < 0.0 || u > 1.0 { return None; }
+        
+        return Some(u * e.length());
+    }
+    
+    pub fn fringe_report(&self, count: usize) -> FringeReport
+    {
+        let profile = self.generate_profile(count, false);
+        let analysis = analyse_fringes(&profile.position, &profile.total, THRESHOLD);
+        let mut markers = vec![Colour::ZERO; count].into_boxed_slice();
+        
+        let mut report = FringeReport::default();
+        if let Some((theory, centre, i)) = self.slit_theory()
+        {
+            let w = &self.walls[i];
+            let n = w.dir.rotated_90();
+            let project = |angles: Vec<(i32, f64)>| -> Vec<f64>
+            {
+                return angles.iter().filter_map(|(_, t)|
+                {
+                    return self.screen_position(centre, (n * t.cos()) + (w.dir * t.sin()));
+                }).collect();
+            };
+            
+            report.theory_maxima = project(theory.interference_maxima());
+            report.theory_minima = project(theory.single_slit_minima());
+            report.theory = Some(theory);
+        }
+        
+        let length = self.env.screen.0.distance(self.env.screen.1);
+        let scale = ((count - 1) as f64) / length;
+        for p in &report.theory_minima
+        {
+            markers[(p * scale).round() as usize] = THEORY_MIN;
+        }
+        for p in &report.theory_maxima
+        {
+            markers[(p * scale).round() as usize] = THEORY_MAX;
+        }
+        for i in &analysis.minima
+        {
+            markers[*i] = MEASURED_MIN;
+        }
+        for i in &analysis.maxima
+        {
+            markers[*i] = MEASURED_MAX;
+        }
+        
+        report.analysis = analysis;
+        report.markers = markers;
+        return report;
+    }
+}
\ No newline at end of file
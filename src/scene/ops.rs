@@ -0,0 +1,111 @@
+use zene_structs::Vector2;
+
+use super::{Scene, SceneSlit, SceneUIRef, Wall};
+
+/// wall and screen positions, with the slits on each wall
+#[derive(Debug, Clone)]
+pub struct Layout
+{
+    walls: Vec<Wall>,
+    screen: (Vector2<f64>, Vector2<f64>)
+}
+
+fn same(a: Vector2<f64>, b: Vector2<f64>) -> bool
+{
+    return a.x == b.x && a.y == b.y;
+}
+
+impl Layout
+{
+    /// whether `scene` still has this layout
+    pub fn matches(&self, scene: &Scene) -> bool
+    {
+        return same(self.screen.0, scene.env.screen.0) && same(self.screen.1, scene.env.screen.1) &&
+            self.walls.len() == scene.walls.len() &&
+            self.walls.iter().zip(&scene.walls).all(|(a, b)|
+                same(a.a, b.a) && same(a.b, b.b) && a.slits == b.slits);
+    }
+}
+
+/// a reversible edit to a scene
+#[derive(Debug, Clone)]
+pub enum SceneOp
+{
+    /// slit added to a wall, sorted by position
+    InsertSlit(usize, SceneSlit),
+    /// wall then slit index
+    DeleteSlit(usize, usize),
+    InsertWall(usize, Wall),
+    DeleteWall(usize),
+    /// any number of moves, such as a drag or fit
+    Layout(Layout)
+}
+
+impl Scene
+{
+    pub fn layout(&self) -> Layout
+    {
+        return Layout {
+            walls: self.walls.clone(),
+            screen: self.env.screen
+        };
+    }
+    
+    /// applies `op`, returning the op that reverses it.
+    /// `None` if `op` does not fit this scene, which is left unchanged
+    pub fn apply(&mut self, op: SceneOp) -> Option<SceneOp>
+    {
+        match op
+        {
+            SceneOp::InsertSlit(wall, slit) =>
+            {
+                let w = self.walls.get_mut(wall)?;
+                let index = w.insert_slit(slit);
+                return Some(SceneOp::DeleteSlit(wall, index));
+            },
+            SceneOp::DeleteSlit(wall, index) =>
+            {
+                let w = self.walls.get_mut(wall)?;
+                if index >= w.slits.len() { return None; }
+                
+                let slit = w.slits.remove(index);
+                return Some(SceneOp::InsertSlit(wall, slit));
+            },
+            SceneOp::InsertWall(index, wall) =>
+            {
+                if index > self.walls.len() { return None; }
+                
+                self.walls.insert(index, wall);
+                return Some(SceneOp::DeleteWall(index));
+            },
+            SceneOp::DeleteWall(index) =>
+            {
+                // always keep one wall
+                if index >= self.walls.len() || self.walls.len() <= 1 { return None; }
+                
+                let wall = self.walls.remove(index);
+                return Some(SceneOp::InsertWall(index, wall));
+            },
+            SceneOp::Layout(layout) =>
+            {
+                if layout.walls.len() == 0 { return None; }
+                
+                let old = self.layout();
+                self.walls = layout.walls;
+                self.env.screen = layout.screen;
+                return Some(SceneOp::Layout(old));
+            }
+        }
+    }
+    
+    /// whether `sur` points to something in this scene
+    pub fn contains_ref(&self, sur: SceneUIRef) -> bool
+    {
+        return match sur
+        {
+            SceneUIRef::Slit(i, j) => self.walls.get(i).is_some_and(|w| j < w.slits.len()),
+            SceneUIRef::Wall(i) | SceneUIRef::Point(i, _) => i < self.walls.len(),
+            _ => true
+        };
+    }
+}
\ No newline at end of file
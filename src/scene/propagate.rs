@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use backend::{add_slit, far_field, sample_far_field, FftPlan};
+use num::{Complex, Zero};
+use zene_structs::Vector3;
+
+use super::Scene;
+
+/// largest grid the angular spectrum solver will use
+const MAX_SAMPLES: usize = 1 << 20;
+/// grid spacing for the shortest wavelength
+const SAMPLES_PER_WAVELENGTH: f64 = 8.0;
+/// grid length over the span of the slits, finer spectra interpolate better
+const OVERSAMPLE: f64 = 16.0;
+/// largest phase in radians the Fresnel correction may miss at the edge of the screen
+const MAX_PHASE_ERROR: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Solver
+{
+    /// sums every slit at every screen sample
+    #[default]
+    Direct,
+    /// transforms the slits once and reads the far field at each screen sample,
+    /// falls back to direct when the scene doesn't suit it
+    AngularSpectrum
+}
+
+/// samples over the slits of a wall, zero is `centre` along the wall
+struct Grid
+{
+    wall: usize,
+    centre: f64,
+    dx: f64,
+    len: usize,
+    distance: f64
+}
+
+impl Scene
+{
+    pub fn set_solver(&mut self, solver: Solver)
+    {
+        self.solver = solver;
+    }
+    pub fn get_solver(&self) -> Solver
+    {
+        return self.solver;
+    }
+    /// whether the angular spectrum solver can run on this scene.
+    /// it needs a single wall with slits, parallel to the screen and far enough away for the slits' spread
+    pub fn can_propagate(&self) -> bool
+    {
+        return self.propagation_grid().is_some();
+    }
+    
+    fn propagation_grid(&self) -> Option<Grid>
+    {
+        let mut walls = self.walls.iter().enumerate().filter(|(_, w)| w.slits.len() != 0);
+        let (i, w) = walls.next()?;
+        if walls.next().is_some() { return None; }
+        
+        let (s0, s1) = self.env.screen;
+        if w.dir.perp_dot((s1 - s0).normalised()).abs() > 1e-6 { return None; }
+        // screen in front of the wall
+        let distance = (s0 - w.a).dot(w.dir.rotated_90());
+        if distance <= 0.0 { return None; }
+        
+        let lambda = self.waves.iter().fold(f64::INFINITY, |a, w| a.min(w.lambda));
+        if !lambda.is_finite() { return None; }
+        let dx = lambda / SAMPLES_PER_WAVELENGTH;
+        
+        let left = w.slits.iter().fold(f64::INFINITY, |a, s| a.min(s.get_left()));
+        let right = w.slits.iter().fold(f64::NEG_INFINITY, |a, s| a.max(s.get_right()));
+        let centre = (left + right) * 0.5;
+        let half = (right - left) * 0.5;
+        
+        // the far field drops the x^2 cos^3 / 2z term of each slit's distance,
+        // the Fresnel correction only gets it right on axis
+        let edge = ((s0 - w.a).dot(w.dir) - centre).abs().max(((s1 - w.a).dot(w.dir) - centre).abs());
+        let cos = distance / distance.hypot(edge);
+        let error = std::f64::consts::PI * half * half * (1.0 - cos.powi(3)) / (lambda * distance);
+        if error.is_nan() || error > MAX_PHASE_ERROR { return None; }
+        
+        let samples = (OVERSAMPLE * 2.0 * half / dx).ceil().max(1.0);
+        if samples.is_nan() || samples > MAX_SAMPLES as f64 { return None; }
+        let len = (samples as usize).next_power_of_two();
+        if len > MAX_SAMPLES { return None; }
+        
+        return Some(Grid {
+            wall: i,
+            centre,
+            dx,
+            len,
+            distance
+        });
+    }
+    /// plan for `len` samples, kept while the grid stays the same size
+    fn propagation_plan(&mut self, len: usize) -> Arc<FftPlan<f64>>
+    {
+        if let Some(plan) = &self.plan
+        {
+            if plan.len() == len { return plan.clone(); }
+        }
+        
+        let plan = Arc::new(FftPlan::new(len));
+        self.plan = Some(plan.clone());
+        return plan;
+    }
+    
+    /// angular spectrum version of `simulate`, false if the scene doesn't suit it
+    pub(super) fn propagate_pattern<S>(&mut self, wave_map: &[(f64, Vector3)], samples: &mut [S]) -> bool
+        where S: From<Vector3>
+    {
+        let grid = match self.propagation_grid()
+        {
+            Some(g) => g,
+            None => return false
+        };
+        let plan = self.propagation_plan(grid.len);
+        let wall = &self.walls[grid.wall];
+        
+        let mut field = vec![Complex::<f64>::zero(); grid.len];
+        let mut totals = vec![Vector3::zero(); samples.len()];
+        let step = 1.0 / ((samples.len().max(2) - 1) as f64);
+        // sine of the angle from the centre of the slits to each sample
+        let sines: Vec<f64> = (0..samples.len()).map(|i|
+        {
+            let p = self.env.lerp(match samples.len() { 1 => 0.5, _ => i as f64 * step });
+            let x = (p - wall.a).dot(wall.dir) - grid.centre;
+            x / x.hypot(grid.distance)
+        }).collect();
+        
+        for wave in self.waves.iter()
+        {
+            let colour = match wave_map.iter().find(|w| w.0 == wave.lambda)
+            {
+                Some(w) => w.1,
+                None => continue
+            };
+            
+            // each slit adds its amplitude on axis, as in the direct solver
+            field.fill(Complex::zero());
+            for s in &wall.slits
+            {
+                let value = Complex::from_polar(wave.amplitude, s.phase);
+                add_slit(&mut field, grid.dx, s.get_left() - grid.centre, s.get_right() - grid.centre, value);
+            }
+            
+            far_field(&plan, &mut field, grid.dx, wave.lambda, grid.distance);
+            
+            for (t, sin) in totals.iter_mut().zip(&sines)
+            {
+                let c = sample_far_field(&field, grid.dx, sin / wave.lambda);
+                *t += colour * (c.norm_sqr() as f32);
+            }
+        }
+        
+        for (s, t) in samples.iter_mut().zip(totals)
+        {
+            *s = t.into();
+        }
+        return true;
+    }
+}
+#[cfg(test)]
+mod tests
+{
+    use backend::Wave;
+    
+    use crate::scene::{SceneSlit, DEFAULT_WIDTH};
+    use super::*;
+    
+    const SAMPLES: usize = 2000;
+    
+    /// default scene lit by three wavelengths, each coloured its own channel
+    fn scene(slits: &[(f64, f64)]) -> (Scene, Vec<(f64, Vector3)>)
+    {
+        let mut scene = Scene::default();
+        scene.waves = [400.0, 550.0, 700.0].iter().map(|l| Wave::new(*l, 1.0)).collect();
+        if !slits.is_empty()
+        {
+            scene.walls[0].slits = slits.iter().map(|(position, width)|
+                SceneSlit { width: *width, position: *position, phase: position * 1e-3 }).collect();
+        }
+        
+        let wave_map = vec![
+            (400.0, Vector3::new(1.0, 0.0, 0.0)),
+            (550.0, Vector3::new(0.0, 1.0, 0.0)),
+            (700.0, Vector3::new(0.0, 0.0, 1.0))];
+        return (scene, wave_map);
+    }
+    
+    /// largest difference between the solvers relative to the brightest sample, per wavelength
+    fn error(scene: &mut Scene, wave_map: &[(f64, Vector3)]) -> f32
+    {
+        let mut direct = vec![Vector3::zero(); SAMPLES];
+        scene.set_solver(Solver::Direct);
+        scene.simulate(wave_map, &mut direct);
+        
+        let mut fast = vec![Vector3::zero(); SAMPLES];
+        assert!(scene.propagate_pattern(wave_map, &mut fast));
+        
+        let channel = |v: &[Vector3], c: usize| -> Vec<f32> { v.iter().map(|v| [v.x, v.y, v.z][c]).collect() };
+        return (0..3).fold(0.0, |e: f32, c|
+        {
+            let a = channel(&direct, c);
+            let b = channel(&fast, c);
+            let peak = a.iter().fold(0.0, |m: f32, v| m.max(*v));
+            let diff = a.iter().zip(&b).fold(0.0, |m: f32, (a, b)| m.max((a - b).abs()));
+            e.max(diff / peak)
+        });
+    }
+    
+    #[test]
+    fn default_scene_matches_direct()
+    {
+        let (mut scene, wave_map) = scene(&[]);
+        assert!(scene.can_propagate());
+        let e = error(&mut scene, &wave_map);
+        assert!(e < 1e-2, "error {e}");
+    }
+    
+    #[test]
+    fn many_slits_match_direct()
+    {
+        let width = DEFAULT_WIDTH;
+        let slits: Vec<(f64, f64)> = (0..40).map(|i| (1e9 + (i as f64 * 4.0 * width), width)).collect();
+        let (mut scene, wave_map) = scene(&slits);
+        assert!(scene.can_propagate());
+        let e = error(&mut scene, &wave_map);
+        assert!(e < 1e-2, "error {e}");
+        
+        // the plan is kept between runs
+        let plan = scene.plan.clone().unwrap();
+        let e = error(&mut scene, &wave_map);
+        assert!(e < 1e-2, "error {e}");
+        assert!(Arc::ptr_eq(&plan, scene.plan.as_ref().unwrap()));
+    }
+    
+    #[test]
+    fn spread_slits_fall_back()
+    {
+        let (scene, _) = scene(&[(0.0, DEFAULT_WIDTH), (2e8, DEFAULT_WIDTH)]);
+        assert!(!scene.can_propagate());
+    }
+}
\ No newline at end of file
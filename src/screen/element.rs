@@ -5,23 +5,23 @@ use iced::Rectangle;
 
 use super::renderer::{Screen, SCREEN_SIZE};
 
-pub fn screen<'a, Message>(colours: &'a [Colour], exposure: f32) -> Shader<Message, ScreenEl<'a>>
+pub fn screen<'a, Message, const ID: usize>(colours: &'a [Colour], exposure: f32) -> Shader<Message, ScreenEl<'a, ID>>
 {
     return shader(
         ScreenEl { colours, exposure }
     ).width(SCREEN_SIZE as f32);
 }
 
-pub struct ScreenEl<'a>
+pub struct ScreenEl<'a, const ID: usize>
 {
     colours: &'a [Colour],
     exposure: f32
 }
 
-impl<'a, Message> Program<Message> for ScreenEl<'a>
+impl<'a, Message, const ID: usize> Program<Message> for ScreenEl<'a, ID>
 {
     type State = ();
-    type Primitive = Screen;
+    type Primitive = Screen<ID>;
 
     fn draw(
         &self,
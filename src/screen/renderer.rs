@@ -9,13 +9,13 @@ use util::BufferInitDescriptor;
 pub const SCREEN_SIZE: u32 = 450;
 
 #[derive(Debug)]
-pub struct Screen
+pub struct Screen<const ID: usize>
 {
     colours: Vec<Colour>,
     exposure: f32
 }
 
-impl Screen
+impl<const ID: usize> Screen<ID>
 {
     pub fn new(colours: Vec<Colour>, exposure: f32) -> Self
     {
@@ -23,7 +23,7 @@ impl Screen
     }
 }
 
-impl Primitive for Screen
+impl<const ID: usize> Primitive for Screen<ID>
 {
     fn prepare(
         &self,
@@ -35,15 +35,15 @@ impl Primitive for Screen
         _bounds: &Rectangle,
         _viewport: &iced::widget::shader::Viewport)
     {
-        let pipe = storage.get_mut::<ScreenPipe>();
+        let pipe = storage.get_mut::<ScreenPipe<ID>>();
         let pipe = match pipe
         {
             Some(lp) => lp,
             None =>
             {
-                let lp = ScreenPipe::new(device, format);
+                let lp = ScreenPipe::<ID>::new(device, format);
                 storage.store(lp);
-                storage.get_mut::<ScreenPipe>().unwrap()
+                storage.get_mut::<ScreenPipe<ID>>().unwrap()
             },
         };
         
@@ -80,7 +80,7 @@ impl Primitive for Screen
         target: &iced::widget::shader::wgpu::TextureView,
         clip_bounds: &Rectangle<u32>)
     {
-        let pipe = storage.get::<ScreenPipe>();
+        let pipe = storage.get::<ScreenPipe<ID>>();
         match pipe
         {
             Some(pipe) =>
@@ -117,7 +117,7 @@ impl Primitive for Screen
     }
 }
 
-struct ScreenPipe
+struct ScreenPipe<const ID: usize>
 {
     render_pipeline: RenderPipeline,
     vertex_buffer: Buffer,
@@ -127,7 +127,7 @@ struct ScreenPipe
     bind_group: BindGroup
 }
 
-impl ScreenPipe
+impl<const ID: usize> ScreenPipe<ID>
 {
     pub fn new(
         device: &iced::widget::shader::wgpu::Device,
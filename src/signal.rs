@@ -0,0 +1,163 @@
+use std::f32::consts::TAU;
+use std::io::BufRead;
+
+use backend::{frame_count, hop_for_frames, read_csv_columns, spectrogram, Colour, RealFftPlan};
+use num::Zero;
+use zene_structs::Vector3;
+
+use crate::spectrogram::renderer::MAX_FRAMES;
+use crate::wave_data::{fill, WaveData};
+
+/// a signal longer than the wave plot, analysed in frames the length of the plot
+#[derive(Debug, Clone, Default)]
+pub struct Signal
+{
+    pub data: Box<[f32]>,
+    /// decimated to the width of its plotter
+    pub view: Box<[f32]>,
+    /// `size.1` rows of `size.0` frames, bin 1 first
+    pub image: Box<[Colour]>,
+    pub size: (usize, usize),
+    pub slice: usize,
+    hop: usize
+}
+
+impl Signal
+{
+    pub fn is_empty(&self) -> bool
+    {
+        return self.data.len() == 0;
+    }
+    pub fn set_data(&mut self, data: Box<[f32]>)
+    {
+        self.data = data;
+        self.slice = 0;
+        self.update_view(self.view.len());
+    }
+    /// values in the second column
+    pub fn read_csv<R: BufRead>(&mut self, reader: R) -> std::io::Result<()>
+    {
+        let values: Vec<(f32, f32)> = read_csv_columns(reader, 0, 1)?;
+        if values.len() == 0
+        {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no samples"));
+        }
+        
+        self.set_data(values.iter().map(|v| v.1).collect());
+        return Ok(());
+    }
+    /// linear sweep from `start` to `end` cycles per sample
+    pub fn chirp(&mut self, len: usize, start: f32, end: f32)
+    {
+        let rate = (end - start) / (len as f32);
+        self.set_data((0..len).map(|i|
+        {
+            let t = i as f32;
+            (TAU * ((start * t) + (0.5 * rate * t * t))).sin()
+        }).collect());
+    }
+    
+    /// each value covers an equal span of samples
+    pub fn update_view(&mut self, width: usize)
+    {
+        let len = self.data.len();
+        if width == 0 || len == 0
+        {
+            self.view = vec![0.0; width].into_boxed_slice();
+            return;
+        }
+        
+        self.view = (0..width).map(|x|
+        {
+            let a = x * len / width;
+            let b = ((x + 1) * len / width).max(a + 1);
+            self.data[a..b].iter().sum::<f32>() / ((b - a) as f32)
+        }).collect();
+    }
+    /// sample range drawn by plotter pixel `x`
+    fn span(&self, x: usize) -> (usize, usize)
+    {
+        let width = self.view.len().max(1);
+        let len = self.data.len();
+        let a = (x * len / width).min(len.saturating_sub(1));
+        let b = ((x + 1) * len / width).clamp(a + 1, len);
+        return (a, b);
+    }
+    pub fn set_point(&mut self, x: usize, value: f32)
+    {
+        if self.is_empty() { return; }
+        
+        let (a, b) = self.span(x);
+        self.data[a..b].fill(value);
+        self.update_view(self.view.len());
+    }
+    pub fn set_line(&mut self, start: (usize, f32), end: (usize, f32))
+    {
+        if self.is_empty() { return; }
+        
+        let (a, _) = self.span(start.0);
+        let (b, _) = self.span(end.0);
+        fill(&mut self.data, (a, start.1), (b, end.1));
+        self.update_view(self.view.len());
+    }
+    
+    /// first sample of frame `i`
+    fn frame_start(&self, i: usize) -> usize
+    {
+        return i * self.hop;
+    }
+    /// spectrogram with the plot's frame length and window, coloured by its wave map
+    pub fn compute(&mut self, plot: &WaveData)
+    {
+        let frame = plot.wave.len();
+        if self.is_empty() || frame < 2
+        {
+            self.image = Default::default();
+            self.size = (0, 0);
+            return;
+        }
+        
+        self.hop = hop_for_frames(self.data.len(), frame, MAX_FRAMES as usize);
+        let frames = frame_count(self.data.len(), frame, self.hop);
+        let plan = RealFftPlan::<f32>::new(frame);
+        let window = plot.get_window().coefficients(frame);
+        let mags = spectrogram(&plan, &window, &self.data, self.hop);
+        
+        // no dc row
+        let bins = (frame / 2).max(1);
+        let max = mags.iter().flat_map(|f| f.iter().skip(1)).fold(0.0f32, |a, m| a.max(*m));
+        let scale = if max > 0.0 { 1.0 / max } else { 0.0 };
+        let map = &plot.wave_map;
+        
+        let mut image = vec![Colour::ZERO; frames * bins];
+        for (x, f) in mags.iter().enumerate()
+        {
+            for (y, m) in f.iter().skip(1).take(bins).enumerate()
+            {
+                let c = match map.len()
+                {
+                    0 => Vector3::zero(),
+                    l => map[(y * l / bins).min(l - 1)].1
+                };
+                image[(y * frames) + x] = (c * (m * scale)).into();
+            }
+        }
+        
+        self.image = image.into_boxed_slice();
+        self.size = (frames, bins);
+        self.slice = self.slice.min(frames - 1);
+    }
+    /// copies frame `i` into the wave plot, returns false if there is no signal
+    pub fn select(&mut self, i: usize, plot: &mut WaveData) -> bool
+    {
+        if self.is_empty() || self.size.0 == 0 { return false; }
+        
+        self.slice = i.min(self.size.0 - 1);
+        let start = self.frame_start(self.slice);
+        for (j, w) in plot.wave.iter_mut().enumerate()
+        {
+            *w = self.data.get(start + j).copied().unwrap_or(0.0);
+        }
+        return true;
+    }
+}
\ No newline at end of file
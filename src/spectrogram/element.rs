@@ -0,0 +1,110 @@
+use backend::Colour;
+use iced::mouse::Button;
+use iced::widget::{shader::{Event, Program}, shader};
+use iced::advanced::graphics::core::event::Status;
+use iced::widget::Shader;
+use iced::Rectangle;
+
+use super::renderer::Spectrogram;
+
+/// `image` is `size.1` rows of `size.0` frames, lowest frequency first
+pub fn spectrogram<'a, F, Message, const ID: usize>(image: &'a [Colour], size: (usize, usize),
+    slice: usize, on_pick: F) -> Shader<Message, SpectrogramEl<'a, F, Message, ID>>
+    where F: Fn(usize) -> Message
+{
+    return shader(
+        SpectrogramEl { image, size, slice, on_pick }
+    );
+}
+
+pub struct SpectrogramEl<'a, F, Message, const ID: usize>
+    where F: Fn(usize) -> Message
+{
+    image: &'a [Colour],
+    size: (usize, usize),
+    slice: usize,
+    on_pick: F
+}
+
+impl<'a, F, Message, const ID: usize> SpectrogramEl<'a, F, Message, ID>
+    where F: Fn(usize) -> Message
+{
+    fn frame(&self, x: f32, bounds: Rectangle) -> Option<Message>
+    {
+        if self.size.0 == 0 { return None; }
+        
+        let u = (x / bounds.width).clamp(0.0, 1.0);
+        let frame = ((u * self.size.0 as f32) as usize).min(self.size.0 - 1);
+        return Some((self.on_pick)(frame));
+    }
+}
+
+impl<'a, F, Message, const ID: usize> Program<Message> for SpectrogramEl<'a, F, Message, ID>
+    where F: Fn(usize) -> Message
+{
+    type State = bool;
+    type Primitive = Spectrogram<ID>;
+    
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _cursor: iced::advanced::mouse::Cursor,
+        _bounds: Rectangle) -> Self::Primitive
+    {
+        return Spectrogram::new(self.image.to_vec(), self.size, self.slice);
+    }
+    
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: iced::advanced::mouse::Cursor,
+        _shell: &mut iced::advanced::Shell<'_, Message>) -> (Status, Option<Message>)
+    {
+        match event
+        {
+            Event::Mouse(iced::mouse::Event::ButtonPressed(Button::Left)) =>
+            {
+                if let Some(cursor_position) = cursor.position_over(bounds)
+                {
+                    *state = true;
+                    let p = cursor_position - bounds.position();
+                    return (Status::Captured, self.frame(p.x, bounds));
+                }
+            },
+            Event::Mouse(iced::mouse::Event::ButtonReleased(Button::Left)) =>
+            {
+                if *state
+                {
+                    *state = false;
+                    return (Status::Captured, None);
+                }
+            },
+            Event::Mouse(iced::mouse::Event::CursorMoved { position }) =>
+            {
+                if *state
+                {
+                    let p = position - bounds.position();
+                    return (Status::Captured, self.frame(p.x, bounds));
+                }
+            },
+            _ => {}
+        }
+        
+        return (Status::Ignored, None);
+    }
+    
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: iced::Rectangle,
+        cursor: iced::advanced::mouse::Cursor) -> iced::advanced::mouse::Interaction
+    {
+        if *state || cursor.is_over(bounds)
+        {
+            return iced::advanced::mouse::Interaction::Pointer;
+        }
+        return iced::advanced::mouse::Interaction::default();
+    }
+}
\ No newline at end of file
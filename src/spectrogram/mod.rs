@@ -0,0 +1,2 @@
+pub mod element;
+pub mod renderer;
\ No newline at end of file
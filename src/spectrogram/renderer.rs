@@ -0,0 +1,270 @@
+use std::fmt::Debug;
+use backend::Colour;
+use iced::widget::shader::wgpu::util::DeviceExt;
+use iced::widget::shader::wgpu::*;
+use iced::widget::shader::Primitive;
+use iced::Rectangle;
+use util::BufferInitDescriptor;
+
+use crate::SPECTRUM_SIZE;
+
+/// most frames the texture can hold
+pub const MAX_FRAMES: u32 = 256;
+
+#[derive(Debug)]
+pub struct Spectrogram<const ID: usize>
+{
+    image: Vec<Colour>,
+    size: (usize, usize),
+    slice: usize
+}
+
+impl<const ID: usize> Spectrogram<ID>
+{
+    pub fn new(image: Vec<Colour>, size: (usize, usize), slice: usize) -> Self
+    {
+        return Self { image, size, slice };
+    }
+}
+
+impl<const ID: usize> Primitive for Spectrogram<ID>
+{
+    fn prepare(
+        &self,
+        device: &iced::widget::shader::wgpu::Device,
+        queue: &iced::widget::shader::wgpu::Queue,
+        format: iced::widget::shader::wgpu::TextureFormat,
+        // custom pipelines go here
+        storage: &mut iced::widget::shader::Storage,
+        _bounds: &Rectangle,
+        _viewport: &iced::widget::shader::Viewport)
+    {
+        let pipe = storage.get_mut::<SpectrogramPipe<ID>>();
+        let pipe = match pipe
+        {
+            Some(lp) => lp,
+            None =>
+            {
+                let lp = SpectrogramPipe::<ID>::new(device, format);
+                storage.store(lp);
+                storage.get_mut::<SpectrogramPipe<ID>>().unwrap()
+            },
+        };
+        
+        let (width, height) = (self.size.0 as u32, self.size.1 as u32);
+        // nothing to show, or too large for the texture
+        let valid = width > 0 && height > 0 && width <= MAX_FRAMES && height <= SPECTRUM_SIZE &&
+            self.image.len() == self.size.0 * self.size.1;
+        let (width, height) = match valid
+        {
+            true => (width, height),
+            false => (0, 0)
+        };
+        
+        queue.write_buffer(&pipe.uniform_buffer, 0,
+            bytemuck::cast_slice(&[width as f32, height as f32, self.slice as f32, 0.0]));
+        
+        if !valid { return; }
+        queue.write_texture(
+            ImageCopyTexture {
+                texture: &pipe.texture,
+                mip_level: 0,
+                aspect: TextureAspect::All,
+                origin: Origin3d::ZERO
+            },
+            &bytemuck::cast_slice(&self.image),
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(16 * width),
+                rows_per_image: None,
+            },
+            Extent3d { width, height, depth_or_array_layers: 1 });
+    }
+    
+    fn render(
+        &self,
+        encoder: &mut iced::widget::shader::wgpu::CommandEncoder,
+        storage: &iced::widget::shader::Storage,
+        target: &iced::widget::shader::wgpu::TextureView,
+        clip_bounds: &Rectangle<u32>)
+    {
+        let pipe = storage.get::<SpectrogramPipe<ID>>();
+        match pipe
+        {
+            Some(pipe) =>
+            {
+                let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                    label: Some("spectrogram.render"),
+                    color_attachments: &[Some(RenderPassColorAttachment {
+                        view: &target,
+                        resolve_target: None,
+                        ops: Operations {
+                            load: LoadOp::Load,
+                            store: StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    occlusion_query_set: None,
+                    timestamp_writes: None
+                });
+                
+                render_pass.set_scissor_rect(clip_bounds.x, clip_bounds.y, clip_bounds.width, clip_bounds.height);
+                render_pass.set_viewport(clip_bounds.x as f32,
+                    clip_bounds.y as f32,
+                    clip_bounds.width as f32,
+                    clip_bounds.height as f32, 0.0, 1.0);
+                render_pass.set_pipeline(&pipe.render_pipeline);
+                render_pass.set_bind_group(0, &pipe.bind_group, &[]);
+                
+                render_pass.set_vertex_buffer(0, pipe.vertex_buffer.slice(..));
+                
+                render_pass.draw(0..4, 0..1);
+            },
+            None => {},
+        };
+    }
+}
+
+struct SpectrogramPipe<const ID: usize>
+{
+    render_pipeline: RenderPipeline,
+    vertex_buffer: Buffer,
+    uniform_buffer: Buffer,
+    texture: Texture,
+    bind_group: BindGroup
+}
+
+impl<const ID: usize> SpectrogramPipe<ID>
+{
+    pub fn new(
+        device: &iced::widget::shader::wgpu::Device,
+        format: iced::widget::shader::wgpu::TextureFormat) -> Self
+    {
+        // used size and selected frame
+        let uniform_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("spectrogram.uniform"),
+            contents: bytemuck::cast_slice(&[0.0f32; 4]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST
+        });
+        
+        let texture = device.create_texture(&TextureDescriptor {
+                size: Extent3d { width: MAX_FRAMES, height: SPECTRUM_SIZE, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba32Float,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                label: Some("spectrogram.image"),
+                view_formats: &[]
+            }
+        );
+        
+        let texture_view = texture.create_view(&TextureViewDescriptor::default());
+        
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("spectrogram.uniform.bind"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: TextureViewDimension::D2,
+                        sample_type: TextureSampleType::Float { filterable: false }
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ]
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("spectrogram.uniform.group"),
+            layout: &bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&texture_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+            ]
+        });
+        
+        let shader = device.create_shader_module(include_wgsl!("spectrogram_shader.wgsl"));
+        let render_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("spectrogram.rp.lay"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[]
+        });
+        
+        let vertex_buffer = device.create_buffer_init(
+            &util::BufferInitDescriptor {
+                label: Some("spectrogram.verts"),
+                contents: bytemuck::cast_slice(&[
+                    // pos              uv
+                    -1.0f32, 1.0f32,    0.0f32, 1.0f32,
+                    1.0f32, 1.0f32,     1.0f32, 1.0f32,
+                    -1.0f32, -1.0f32,   0.0f32, 0.0f32,
+                    1.0f32, -1.0f32,    1.0f32, 0.0f32
+                ]),
+                usage: BufferUsages::VERTEX
+            }
+        );
+        
+        let buffer_layout = VertexBufferLayout {
+            array_stride: std::mem::size_of::<[f32; 4]>() as BufferAddress,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &vertex_attr_array![0 => Float32x2, 1 => Float32x2]
+        };
+        
+        let render_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("spectrogram.pipe"),
+            layout: Some(&render_pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[buffer_layout]
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })]
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleStrip,
+                front_face: FrontFace::Cw,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None
+        });
+        
+        return Self {
+            render_pipeline,
+            vertex_buffer,
+            uniform_buffer,
+            texture,
+            bind_group
+        };
+    }
+}
\ No newline at end of file
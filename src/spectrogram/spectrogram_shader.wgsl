@@ -0,0 +1,52 @@
+struct VertexIn
+{
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>
+}
+
+struct VertexOut
+{
+    @builtin(position) pos: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+}
+
+struct View
+{
+    // frames and bins in use
+    size: vec2<f32>,
+    slice: f32,
+    pad: f32
+}
+
+@group(0) @binding(0)
+var image: texture_2d<f32>;
+@group(0) @binding(1)
+var<uniform> view: View;
+
+@vertex
+fn vs_main(in: VertexIn) -> VertexOut
+{
+    var out: VertexOut;
+    out.pos = vec4<f32>(in.position, 0.0, 1.0);
+    out.uv = in.uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOut) -> @location(0) vec4<f32>
+{
+    if (view.size.x < 1.0 || view.size.y < 1.0)
+    {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+    
+    let x = u32(min(in.uv.x * view.size.x, view.size.x - 1.0));
+    let y = u32(min(in.uv.y * view.size.y, view.size.y - 1.0));
+    let colour = textureLoad(image, vec2<u32>(x, y), 0).rgb;
+    // marks the selected frame
+    if (x == u32(view.slice))
+    {
+        return vec4<f32>(mix(colour, vec3<f32>(1.0, 1.0, 1.0), 0.5), 1.0);
+    }
+    return vec4<f32>(colour, 1.0);
+}
\ No newline at end of file
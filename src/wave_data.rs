@@ -1,5 +1,10 @@
-use backend::{dft_analysis, form_plot, wave_length_colour, WCache};
+use std::f32::consts::PI;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use backend::{coherent_gain, dft_analysis, form_plot, minimum_phase, ColourMap, Padding, RealFftPlan, SourceSpectrum, SpectralFilter, Window};
 use num::{complex::Complex32, NumCast, traits::{ConstOne, NumOps}};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use zene_structs::Vector3;
 
 pub fn fill<F>(plot: &mut [F], start: (usize, F), end: (usize, F))
@@ -62,7 +67,68 @@ fn remap(a: &[f32], b: &mut [f32])
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// simulation band in nm
+pub const DEFAULT_BAND: (f64, f64) = (400.0, 700.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WaveMapping
+{
+    /// bins spread evenly across the simulation band
+    #[default]
+    Linear,
+    /// bin k is the kth harmonic of the given fundamental wavelength
+    Harmonic(f64)
+}
+
+/// phase given to the spectrum when its amplitudes are edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PhaseMode
+{
+    /// measured or drawn phase
+    #[default]
+    Keep,
+    Zero,
+    /// from the cepstrum of the amplitudes
+    Minimum,
+    /// uniform in -pi to pi from the seed
+    Random(u64)
+}
+
+impl PhaseMode
+{
+    /// every mode, random with `seed`
+    pub fn all(seed: u64) -> [PhaseMode; 4]
+    {
+        return [PhaseMode::Keep, PhaseMode::Zero, PhaseMode::Minimum, PhaseMode::Random(seed)];
+    }
+}
+
+impl Display for PhaseMode
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        return f.write_str(match self
+        {
+            PhaseMode::Keep => "Keep phase",
+            PhaseMode::Zero => "Zero phase",
+            PhaseMode::Minimum => "Minimum phase",
+            PhaseMode::Random(_) => "Random phase"
+        });
+    }
+}
+
+/// the parts of the plot that are edited, for undo
+#[derive(Debug, Clone)]
+pub struct PlotSnapshot
+{
+    wave: Box<[f32]>,
+    dft: Vec<Complex32>,
+    /// kept as well, the dft loses it where the amplitude is zero
+    phase: Box<[f32]>,
+    phase_mode: PhaseMode
+}
+
+#[derive(Debug, Clone)]
 pub struct WaveData
 {
     pub wave: Box<[f32]>,
@@ -71,26 +137,164 @@ pub struct WaveData
     pub dft: Vec<Complex32>,
     pub wave_map: Box<[(f64, Vector3)]>,
     scale: f32,
-    use_phase: bool
+    phase_mode: PhaseMode,
+    mapping: WaveMapping,
+    band: (f64, f64),
+    colour_map: ColourMap,
+    plan: Option<Arc<RealFftPlan<f32>>>,
+    window: Window<f32>,
+    padding: Padding,
+    /// window for the current plot size
+    coefficients: Box<[f32]>
+}
+
+impl Default for WaveData
+{
+    fn default() -> Self
+    {
+        return Self {
+            wave: Default::default(),
+            spectrum: Default::default(),
+            phase: Default::default(),
+            dft: Default::default(),
+            wave_map: Default::default(),
+            scale: Default::default(),
+            phase_mode: Default::default(),
+            mapping: Default::default(),
+            band: DEFAULT_BAND,
+            colour_map: Default::default(),
+            plan: None,
+            window: Default::default(),
+            padding: Default::default(),
+            coefficients: Default::default()
+        };
+    }
 }
 
 impl WaveData
 {
-    pub fn compute_dft(&mut self, wn: &mut WCache<f32>)
+    /// plan for the current plot size
+    fn plan(&mut self) -> Arc<RealFftPlan<f32>>
+    {
+        if let Some(plan) = &self.plan
+        {
+            if plan.len() == self.padding.len(self.wave.len()) { return plan.clone(); }
+        }
+        
+        let plan = Arc::new(RealFftPlan::new(self.padding.len(self.wave.len())));
+        self.plan = Some(plan.clone());
+        return plan;
+    }
+    /// the dft is of the raw wave so it can be edited and resynthesised,
+    /// the window only changes the amplitudes shown
+    pub fn compute_dft(&mut self)
     {
         if self.wave.len() == 0 { return; }
         
-        wn.set_invert(true);
-        self.dft = dft_analysis(wn, &self.wave);
+        let plan = self.plan();
+        let gain = self.padding.gain::<f32>();
+        self.dft = dft_analysis(&plan, &self.padding.apply(&self.wave));
+        // amplitudes shouldn't depend on the padding
+        for c in &mut self.dft
+        {
+            *c = *c / gain;
+        }
         self.update_spec_phase();
     }
-    pub fn compute_plot(&mut self, wn: &mut WCache<f32>)
+    pub fn compute_plot(&mut self)
+    {
+        if self.dft.len() == 0 { return; }
+        
+        let plan = self.plan();
+        let gain = self.padding.gain::<f32>();
+        let dft: Vec<Complex32> = self.dft.iter().map(|c| c * gain).collect();
+        let plot = form_plot(&plan, &dft);
+        
+        // padding is dropped
+        for (w, p) in self.wave.iter_mut().zip(&plot)
+        {
+            *w = *p;
+        }
+        self.update_amplitudes();
+    }
+    /// dft of the windowed wave for display, none without a window
+    fn windowed_dft(&mut self) -> Option<Vec<Complex32>>
+    {
+        if self.window == Window::Rectangular || self.wave.len() == 0 { return None; }
+        
+        self.update_window();
+        let windowed: Vec<f32> = self.wave.iter().zip(self.coefficients.iter())
+            .map(|(w, c)| w * c).collect();
+        
+        let plan = self.plan();
+        let gain = self.gain();
+        let dft = dft_analysis(&plan, &self.padding.apply(&windowed));
+        // amplitudes shouldn't depend on the window or padding
+        return Some(dft.iter().map(|c| c / gain).collect());
+    }
+    /// shown amplitudes after the dft or wave changed
+    fn update_amplitudes(&mut self)
+    {
+        let s = self.scale / (self.dft.len() as f32);
+        let windowed = self.windowed_dft();
+        let dft = windowed.as_ref().unwrap_or(&self.dft);
+        for (v, c) in self.spectrum.iter_mut().zip(dft.iter().skip(1))
+        {
+            v[0] = c.norm() * s;
+        }
+    }
+    fn update_window(&mut self)
+    {
+        if self.coefficients.len() == self.wave.len() { return; }
+        
+        self.coefficients = self.window.coefficients(self.wave.len()).into_boxed_slice();
+    }
+    /// how much the window and padding scale a sinusoid's peak
+    fn gain(&self) -> f32
+    {
+        return coherent_gain(&self.coefficients) * self.padding.gain::<f32>();
+    }
+    pub fn set_window(&mut self, window: Window<f32>)
+    {
+        if self.window == window { return; }
+        
+        self.window = window;
+        self.coefficients = Default::default();
+    }
+    pub fn get_window(&self) -> Window<f32>
+    {
+        return self.window;
+    }
+    pub fn set_padding(&mut self, padding: Padding)
+    {
+        self.padding = padding;
+    }
+    pub fn get_padding(&self) -> Padding
     {
+        return self.padding;
+    }
+    pub fn snapshot(&self) -> PlotSnapshot
+    {
+        return PlotSnapshot {
+            wave: self.wave.clone(),
+            dft: self.dft.clone(),
+            phase: self.phase.clone(),
+            phase_mode: self.phase_mode
+        };
+    }
+    /// spectrum is recomputed from the restored dft
+    pub fn restore(&mut self, snapshot: PlotSnapshot)
+    {
+        self.wave = snapshot.wave;
+        self.dft = snapshot.dft;
+        self.phase_mode = snapshot.phase_mode;
         if self.dft.len() == 0 { return; }
         
-        wn.set_invert(false);
-        let plot = form_plot(wn, &self.dft, self.wave.len());
-        self.wave = plot.iter().map(|c| c.re).collect();
+        self.update_spec_phase();
+        if self.phase.len() == snapshot.phase.len()
+        {
+            self.phase = snapshot.phase;
+        }
     }
     pub fn resize(&mut self, size: usize)
     {
@@ -102,13 +306,60 @@ impl WaveData
     }
     fn generate_wave_map(&mut self, size: usize)
     {
-        let t = 300.0 / ((size - 1) as f32);
+        let (min, max) = self.band;
+        let t = (max - min) / ((size - 1) as f64);
+        let mapping = self.mapping;
+        let band = (min as f32, max as f32);
+        let colour_map = &self.colour_map;
         self.wave_map = (0..size).into_iter().map(|i|
         {
-            let l = 700.0 - (i as f32 * t);
-            return (l as f64, wave_length_colour(l, 0.8));
+            let l = match mapping
+            {
+                WaveMapping::Linear => max - (i as f64 * t),
+                // frequency k * f0
+                WaveMapping::Harmonic(f) => f / ((i + 1) as f64)
+            };
+            return (l, colour_map.colour(l as f32, band, 0.8));
         }).collect();
     }
+    fn regenerate_wave_map(&mut self)
+    {
+        let size = self.dft.len();
+        if size < 2 { return; }
+        
+        self.generate_wave_map(size - 1);
+        self.update_spec_phase();
+    }
+    pub fn set_mapping(&mut self, mapping: WaveMapping)
+    {
+        if self.mapping == mapping { return; }
+        self.mapping = mapping;
+        self.regenerate_wave_map();
+    }
+    pub fn get_mapping(&self) -> WaveMapping
+    {
+        return self.mapping;
+    }
+    pub fn set_band(&mut self, band: (f64, f64))
+    {
+        if self.band == band { return; }
+        self.band = band;
+        self.regenerate_wave_map();
+    }
+    pub fn get_band(&self) -> (f64, f64)
+    {
+        return self.band;
+    }
+    pub fn set_colour_map(&mut self, colour_map: ColourMap)
+    {
+        if self.colour_map == colour_map { return; }
+        self.colour_map = colour_map;
+        self.regenerate_wave_map();
+    }
+    pub fn get_colour_map(&self) -> &ColourMap
+    {
+        return &self.colour_map;
+    }
     pub fn update_spec_phase(&mut self)
     {
         let size = self.dft.len();
@@ -117,13 +368,12 @@ impl WaveData
             self.generate_wave_map(size - 1);
         }
         
-        let s = self.scale / (size as f32);
-        self.spectrum = self.dft.iter().skip(1).zip(&self.wave_map).map(|p|
+        self.spectrum = self.wave_map.iter().map(|w|
         {
-            let c = p.1.1;
-            let amp = p.0.norm();
-            return [amp * s, c.x, c.y, c.z];
+            let c = w.1;
+            return [0.0, c.x, c.y, c.z];
         }).collect();
+        self.update_amplitudes();
         
         self.phase = self.dft.iter().skip(1).map(|c| c.arg()).collect();
     }
@@ -142,9 +392,51 @@ impl WaveData
     {
         return self.scale;
     }
-    pub fn set_phase(&mut self, phase: bool)
+    /// `compute_plot` resynthesises the wave
+    pub fn set_phase_mode(&mut self, mode: PhaseMode)
+    {
+        self.phase_mode = mode;
+        self.apply_phase_mode();
+    }
+    pub fn get_phase_mode(&self) -> PhaseMode
+    {
+        return self.phase_mode;
+    }
+    /// gives every bin the phase of the mode, amplitudes are unchanged
+    fn apply_phase_mode(&mut self)
+    {
+        if self.dft.len() < 2 { return; }
+        
+        let count = self.phase_bins();
+        let phases: Vec<f32> = match self.phase_mode
+        {
+            PhaseMode::Keep => return,
+            PhaseMode::Zero => vec![0.0; count],
+            PhaseMode::Minimum =>
+            {
+                let plan = self.plan();
+                let mags: Vec<f32> = self.dft.iter().map(|c| c.norm()).collect();
+                minimum_phase(&plan, &mags).iter().skip(1).map(|c| c.arg()).collect()
+            },
+            PhaseMode::Random(seed) =>
+            {
+                let mut rng = StdRng::seed_from_u64(seed);
+                (0..count).map(|_| rng.random_range(-PI..PI)).collect()
+            }
+        };
+        
+        for ((c, p), phase) in self.dft.iter_mut().skip(1).zip(self.phase.iter_mut()).zip(phases)
+        {
+            *c = Complex32::from_polar(c.norm(), phase);
+            *p = phase;
+        }
+    }
+    /// bins after dc whose phase can be set, the nyquist bin of an even transform is real
+    fn phase_bins(&self) -> usize
     {
-        self.use_phase = phase;
+        let size = self.dft.len().saturating_sub(1);
+        if self.padding.len(self.wave.len()) % 2 == 0 { return size.saturating_sub(1); }
+        return size;
     }
     
     pub fn set_plot_point(&mut self, index: usize, value: f32)
@@ -155,76 +447,146 @@ impl WaveData
     {
         fill(&mut self.wave, start, end);
     }
+    /// amplitude of bin `index + 1` without the shown scale or window
+    pub fn get_amplitude(&self, index: usize) -> f32
+    {
+        return self.dft[index + 1].norm() / (self.dft.len() as f32);
+    }
+    /// as `get_amplitude`, the bin keeps its phase
+    pub fn set_amplitude(&mut self, index: usize, amp: f32)
+    {
+        self.set_bin(index, amp);
+        // minimum phase depends on every amplitude
+        self.apply_phase_mode();
+    }
+    /// `set_amplitude` without the phase mode, for bulk edits
+    fn set_bin(&mut self, index: usize, amp: f32)
+    {
+        self.spectrum[index][0] = amp * self.scale;
+        self.dft[index + 1] = Complex32::from_polar(amp * (self.dft.len() as f32), self.phase[index]);
+    }
+    /// `value` is in the shown units
     pub fn set_spec_point(&mut self, index: usize, value: f32)
     {
-        let old = self.spectrum[index][0];
-        self.spectrum[index][0] = value;
+        self.set_amplitude(index, value / self.scale);
+    }
+    /// filled linearly in the heights given by the ends, `value` turns a height into a shown amplitude
+    pub fn set_spec_line<V: Fn(f32) -> f32>(&mut self, start: (usize, f32), end: (usize, f32), value: V)
+    {
+        let s = (self.dft.len() as f32) / self.scale;
         
-        if !self.use_phase
+        let mut iter = self.spectrum.iter_mut().zip(&self.phase).zip(self.dft.iter_mut().skip(1));
+        fill_format(&mut iter, start, end, |out, h|
         {
-            self.dft[index + 1] = Complex32::new(value * (self.dft.len() as f32) / self.scale, 0.0);
-            return;
+            let amp = value(h);
+            out.0.0[0] = amp;
+            *out.1 = Complex32::from_polar(amp * s, *out.0.1);
+        });
+        // minimum phase waits for `finish_spec_line`
+        if self.phase_mode != PhaseMode::Minimum
+        {
+            self.apply_phase_mode();
         }
+    }
+    /// end of a drag of the spectrum, true if the dft changed
+    pub fn finish_spec_line(&mut self) -> bool
+    {
+        if self.phase_mode != PhaseMode::Minimum { return false; }
+        
+        self.apply_phase_mode();
+        return true;
+    }
+    /// drawn phase is kept from then on
+    pub fn set_phase_point(&mut self, index: usize, value: f32)
+    {
+        self.phase_mode = PhaseMode::Keep;
+        self.phase[index] = value;
         
-        if old == 0.0
+        let amp = self.dft[index + 1].norm();
+        // new phase
+        self.dft[index + 1] = Complex32::from_polar(amp, value);
+    }
+    /// multiplies every amplitude by `factor`
+    pub fn scale_spectrum(&mut self, factor: f32)
+    {
+        for i in 0..self.spectrum.len()
         {
-            let phase = self.phase[index];
-            self.dft[index + 1] = Complex32::from_polar(
-                value * (self.dft.len() as f32) / self.scale,
-                phase);
-            return;
+            self.set_bin(i, self.get_amplitude(i) * factor);
         }
-        
-        // rescale value
-        self.dft[index + 1] = self.dft[index + 1] * (value / old);
+        self.apply_phase_mode();
     }
-    pub fn set_spec_line(&mut self, start: (usize, f32), end: (usize, f32))
+    /// scales so the largest amplitude is `peak`, as for `get_amplitude`
+    pub fn normalise_spectrum(&mut self, peak: f32)
     {
-        let s = (self.dft.len() as f32) / self.scale;
+        let max = (0..self.spectrum.len()).fold(0.0f32, |m, i| m.max(self.get_amplitude(i)));
+        if max <= 0.0 { return; }
         
-        let mut iter = self.spectrum.iter_mut().zip(&self.phase).zip(self.dft.iter_mut().skip(1));
-        if self.use_phase
+        self.scale_spectrum(peak / max);
+    }
+    /// zeroes the odd or even harmonics, bin `i` is harmonic `i + 1`
+    pub fn zero_harmonics(&mut self, odd: bool)
+    {
+        for i in 0..self.spectrum.len()
         {
-            fill_format(&mut iter, start, end, |out, amp|
+            if ((i + 1) % 2 == 1) == odd
             {
-                let old = out.0.0[0];
-                out.0.0[0] = amp;
-                // compute new complex value
-                match old
-                {
-                    0.0 => *out.1 = Complex32::from_polar(amp * s, *out.0.1),
-                    // keep direction (phase)
-                    _ => *out.1 = *out.1 * (amp / old)
-                }
-            });
-            return;
+                self.set_bin(i, 0.0);
+            }
+        }
+        self.apply_phase_mode();
+    }
+    /// uniform phases in -pi to pi, kept from then on
+    pub fn randomise_phases<R: Rng>(&mut self, rng: &mut R)
+    {
+        for i in 0..self.phase_bins()
+        {
+            self.set_phase_point(i, rng.random_range(-PI..PI));
         }
+    }
+    /// filters the unwindowed dft, frequencies are in cycles across the plot.
+    /// `compute_plot` resynthesises the wave
+    pub fn apply_filter(&mut self, filter: &SpectralFilter<f32>)
+    {
+        if self.dft.len() == 0 { return; }
         
-        fill_format(&mut iter, start, end, |out, amp|
+        // padding adds bins between harmonics
+        let step = (self.wave.len() as f32) / (self.padding.len(self.wave.len()) as f32);
+        filter.apply(&mut self.dft, step);
+        self.apply_phase_mode();
+        self.update_spec_phase();
+    }
+    pub fn set_spectrum(&mut self, amplitudes: &[f32])
+    {
+        if self.dft.len() == 0 { return; }
+        
+        let s = self.dft.len() as f32;
+        let iter = self.dft.iter_mut().skip(1).zip(amplitudes).zip(self.phase.iter());
+        for ((c, amp), phase) in iter
         {
-            out.0.0[0] = amp;
-            *out.1 = Complex32::new(amp * s, 0.0);
-        });
+            *c = Complex32::from_polar(amp * s, *phase);
+        }
+        
+        self.apply_phase_mode();
+        self.update_spec_phase();
     }
-    pub fn set_phase_point(&mut self, index: usize, value: f32)
+    pub fn apply_source(&mut self, source: &SourceSpectrum<f64>, line_width: f64)
     {
-        self.phase[index] = value;
+        let lambdas: Vec<f64> = self.wave_map.iter().map(|w| w.0).collect();
+        let amps: Vec<f32> = source.sample(&lambdas, line_width).iter()
+            .map(|a| *a as f32).collect();
         
-        let amp = self.spectrum[index][0] * (self.dft.len() as f32) / self.scale;
-        // new phase
-        self.dft[index + 1] = Complex32::from_polar(amp, value);
+        self.set_spectrum(&amps);
     }
     pub fn set_phase_line(&mut self, start: (usize, f32), end: (usize, f32))
     {
-        let s = (self.dft.len() as f32) / self.scale;
+        self.phase_mode = PhaseMode::Keep;
         
-        let mut iter = self.phase.iter_mut().zip(&self.spectrum).zip(self.dft.iter_mut().skip(1));
+        let mut iter = self.phase.iter_mut().zip(self.dft.iter_mut().skip(1));
         fill_format(&mut iter, start, end, |o, new|
         {
-            *o.0.0 = new;
-            let amp = o.0.1[0] * s;
+            *o.0 = new;
             // compute new complex
-            *o.1 = Complex32::from_polar(amp, new);
+            *o.1 = Complex32::from_polar(o.1.norm(), new);
         });
     }
 }
\ No newline at end of file